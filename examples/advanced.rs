@@ -63,6 +63,7 @@ impl From<Config> for CharacterController {
             skin_width: config.skin_width,
             horizontal_ray_count: config.horizontal_ray_count,
             vertical_ray_count: config.vertical_ray_count,
+            ..Default::default()
         }
     }
 }
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use impacted::CollisionShape;
+
+use crate::{
+    collisions::{bounds_overlap, world_bounds, CollisionInfo, JumpCount, PlayerVelocity},
+    impulse::InputLockout,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct ClimbPlugin;
+
+impl Plugin for ClimbPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Climbable>()
+            .register_type::<Climb>()
+            .add_system(
+                climb
+                    .label(ControllerLabel::Calculate)
+                    .before(ControllerLabel::Input),
+            );
+    }
+}
+
+/// Marks a collider as climbable, such as a ladder or vines.
+///
+/// Climbable colliders don't block movement, but their top can be stood on.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Climbable;
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Climb {
+    pub(crate) climbing: bool,
+}
+
+impl Climb {
+    pub fn is_climbing(&self) -> bool {
+        self.climbing
+    }
+}
+
+fn climb(
+    input: Res<Input<KeyCode>>,
+    mut controllers: Query<
+        (
+            &mut Climb,
+            &mut PlayerVelocity,
+            &mut JumpCount,
+            &CollisionShape,
+            &Transform,
+            &CollisionInfo,
+            &InputLockout,
+        ),
+        With<CharacterController>,
+    >,
+    climbables: Query<
+        (Entity, &CollisionShape, &Transform),
+        (With<Climbable>, Without<CharacterController>),
+    >,
+) {
+    for (mut climb, mut velocity, mut jump_count, collider, transform, collisions, input_lockout) in
+        controllers.iter_mut()
+    {
+        let locked_out = input_lockout.is_locked();
+        let up = !locked_out && input.pressed(KeyCode::Up) && !input.pressed(KeyCode::Down);
+        let down = !locked_out && input.pressed(KeyCode::Down) && !input.pressed(KeyCode::Up);
+
+        let bounds = world_bounds(collider, transform);
+        let overlapping = climbables
+            .iter()
+            .any(|(_, climbable, climbable_transform)| {
+                bounds_overlap(bounds, world_bounds(climbable, climbable_transform))
+            });
+
+        if climb.climbing {
+            // Climbed off the top, or reached the bottom
+            if !overlapping || (collisions.below && down) {
+                climb.climbing = false;
+            }
+            continue;
+        }
+
+        let standing_on_top = collisions.below
+            && collisions
+                .ground
+                .map_or(false, |ground| climbables.get(ground).is_ok());
+        let grab = (overlapping && up)
+            || (down && (standing_on_top || (overlapping && !collisions.below)));
+
+        if grab {
+            climb.climbing = true;
+            velocity.0.y = 0.0;
+            // Grabbing on refreshes air jumps
+            jump_count.0 = 0;
+        }
+    }
+}
@@ -1,13 +1,38 @@
-use bevy::{core::Stopwatch, prelude::*};
+use bevy::{core::Stopwatch, prelude::*, utils::HashMap};
 #[cfg(feature = "debug")]
 use bevy_prototype_lyon::prelude::*;
 use impacted::CollisionShape;
 
 use crate::{
-    ray_cast::{Ray, RayCast},
+    climb::{Climb, Climbable},
+    controller::CollisionMode,
+    gravity::{Gravity, GravityZone},
+    push::{push_distance, Pushable, Pushed},
+    ray_cast::{Boxes, Edge, Excluding, Oriented, Ray, RayCast, RayHit},
+    surface::SurfaceVelocity,
+    water::Water,
     CharacterController, ControllerLabel,
 };
 
+/// Distance past the edge of an obstacle to probe when looking for its corner.
+const CORNER_PROBE_INSET: f32 = 0.01;
+
+/// Distance a corner must be above the floor found after moving horizontally for it to stop a
+/// diagonal move early, so landing diagonally on flat ground keeps its horizontal movement.
+const DIAGONAL_CONTACT_TOLERANCE: f32 = 0.01;
+
+/// Maximum passes for pushing a character out of overlapping colliders, in case being pushed out
+/// of one collider pushes it into another.
+const MAX_DEPENETRATION_PASSES: usize = 4;
+
+/// Colliders which block movement.
+pub(crate) type SolidFilter = (
+    Without<CharacterController>,
+    Without<Climbable>,
+    Without<Water>,
+    Without<GravityZone>,
+);
+
 pub(crate) struct CollisionsPlugin;
 
 impl Plugin for CollisionsPlugin {
@@ -19,23 +44,50 @@ impl Plugin for CollisionsPlugin {
             .register_type::<JumpCount>()
             .register_type::<CoyoteStopwatch>()
             .add_event::<CollisionEvent>()
-            .add_system(calculate_ray_spacing.label(ControllerLabel::Calculate))
-            .add_system(update_ray_origins.label(ControllerLabel::Calculate))
+            .add_system(depenetrate.before(ControllerLabel::Calculate))
+            .add_system(
+                calculate_ray_spacing
+                    .label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::RayOrigins),
+            )
+            .add_system(
+                update_ray_origins
+                    .label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::RayOrigins),
+            )
+            .add_system(
+                sweep
+                    .after(ControllerLabel::Calculate)
+                    .before(ControllerLabel::Collisions),
+            )
             .add_system(
                 horizontal_collisions
                     .label(ControllerLabel::Collisions)
+                    .label(ControllerLabel::HorizontalCollisions)
                     .after(ControllerLabel::Calculate),
             )
             .add_system(
                 vertical_collisions
                     .label(ControllerLabel::Collisions)
-                    .after(ControllerLabel::Calculate),
+                    .after(ControllerLabel::Calculate)
+                    .after(ControllerLabel::HorizontalCollisions),
+            )
+            .add_system(
+                restore_velocity
+                    .label(ControllerLabel::RestoreVelocity)
+                    .after(ControllerLabel::Move),
+            )
+            .add_system(
+                reset_jumps
+                    .label(ControllerLabel::Events)
+                    .after(ControllerLabel::Collisions),
             )
-            .add_system(reset_jumps.after(ControllerLabel::Collisions))
             .add_system(coyote_time.after(ControllerLabel::Collisions));
     }
 }
 
+/// Movement for the current frame, relative to the character's [`Gravity`] so `-y` is always
+/// towards the ground.
 #[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
 pub struct PlayerVelocity(pub Vec2);
@@ -47,6 +99,8 @@ pub struct RaySpacing {
     vertical: f32,
 }
 
+/// Sides of the character touching colliders, relative to its [`Gravity`] so `below` is always
+/// the ground.
 #[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
 pub struct CollisionInfo {
@@ -54,15 +108,27 @@ pub struct CollisionInfo {
     pub below: bool,
     pub left: bool,
     pub right: bool,
+    /// Collider being stood on.
+    #[reflect(ignore)]
+    pub ground: Option<Entity>,
+    /// Wall being pressed against.
+    #[reflect(ignore)]
+    pub wall: Option<Entity>,
+    /// Pushable collider being pushed.
+    #[reflect(ignore)]
+    pub pushing: Option<Entity>,
+    stepped: bool,
+    carry: Vec2,
 }
 
+/// Corners of the character inset by the skin width, relative to its [`Gravity`].
 #[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
 pub struct RaycastOrigins {
-    top_left: Vec2,
-    top_right: Vec2,
-    bottom_left: Vec2,
-    bottom_right: Vec2,
+    pub(crate) top_left: Vec2,
+    pub(crate) top_right: Vec2,
+    pub(crate) bottom_left: Vec2,
+    pub(crate) bottom_right: Vec2,
 }
 
 #[derive(Component, Debug, Default, Reflect)]
@@ -81,8 +147,8 @@ struct DebugShapeVertical;
 
 #[derive(Clone, Copy, Debug)]
 pub struct CollisionEvent {
-    entity: Entity,
-    side: CollisionSide,
+    pub entity: Entity,
+    pub side: CollisionSide,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -93,20 +159,79 @@ pub enum CollisionSide {
     Right(bool),
 }
 
+/// Pushes characters out of colliders they overlap, such as after spawning inside one or a
+/// collider moving into them, since rays starting inside a collider miss it.
+fn depenetrate(
+    mut controllers: Query<(&CollisionShape, &mut Transform), With<CharacterController>>,
+    colliders: Query<(&CollisionShape, &Transform), SolidFilter>,
+) {
+    for (collider, mut transform) in controllers.iter_mut() {
+        for _ in 0..MAX_DEPENETRATION_PASSES {
+            let shape = collider.clone().with_transform(*transform);
+            let bounds = world_bounds(collider, &transform);
+
+            // Shapes are tested exactly, then separated along the shortest way out of their bounds
+            let translation = colliders
+                .iter()
+                .filter(|(other, other_transform)| {
+                    shape.is_collided_with(&(*other).clone().with_transform(**other_transform))
+                })
+                .filter_map(|(other, other_transform)| {
+                    minimum_translation(bounds, world_bounds(other, other_transform))
+                })
+                .fold(None, |deepest: Option<Vec2>, translation| match deepest {
+                    Some(deepest) if deepest.length() >= translation.length() => Some(deepest),
+                    _ => Some(translation),
+                });
+
+            match translation {
+                Some(translation) => transform.translation += translation.extend(0.0),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Shortest translation moving bounds `a` out of bounds `b`, both as `(min, max)`.
+fn minimum_translation((min_a, max_a): (Vec2, Vec2), (min_b, max_b): (Vec2, Vec2)) -> Option<Vec2> {
+    let left = max_a.x - min_b.x;
+    let right = max_b.x - min_a.x;
+    let down = max_a.y - min_b.y;
+    let up = max_b.y - min_a.y;
+    if left <= 0.0 || right <= 0.0 || down <= 0.0 || up <= 0.0 {
+        return None;
+    }
+
+    let x = if left < right { -left } else { right };
+    let y = if down < up { -down } else { up };
+    if x.abs() < y.abs() {
+        Some(Vec2::new(x, 0.0))
+    } else {
+        Some(Vec2::new(0.0, y))
+    }
+}
+
 fn calculate_ray_spacing(
     mut query: Query<
-        (&mut RaySpacing, &CharacterController, &CollisionShape),
-        Or<(Changed<CharacterController>, Changed<CollisionShape>)>,
+        (
+            &mut RaySpacing,
+            &CharacterController,
+            &CollisionShape,
+            &Gravity,
+        ),
+        Or<(
+            Changed<CharacterController>,
+            Changed<CollisionShape>,
+            Changed<Gravity>,
+        )>,
     >,
 ) {
-    for (mut ray_spacing, controller, collider) in query.iter_mut() {
-        let bounds = collider.bounds().expand(Vec2::new(
-            controller.skin_width * -2.0,
-            controller.skin_width * -2.0,
-        ));
+    for (mut ray_spacing, controller, collider, gravity) in query.iter_mut() {
+        let size = gravity.to_local(collider.bounds().size()).abs()
+            - Vec2::splat(controller.skin_width * 2.0);
 
-        ray_spacing.horizontal = bounds.size().y / (controller.horizontal_ray_count - 1) as f32;
-        ray_spacing.vertical = bounds.size().x / (controller.vertical_ray_count - 1) as f32;
+        ray_spacing.horizontal = size.y / (controller.horizontal_ray_count - 1) as f32;
+        ray_spacing.vertical = size.x / (controller.vertical_ray_count - 1) as f32;
     }
 }
 
@@ -117,35 +242,24 @@ fn update_ray_origins(
             &CharacterController,
             &Transform,
             &CollisionShape,
+            &Gravity,
         ),
         Or<(
             Changed<CharacterController>,
             Changed<Transform>,
             Changed<CollisionShape>,
+            Changed<Gravity>,
         )>,
     >,
 ) {
-    for (mut ray_origins, controller, transform, collider) in query.iter_mut() {
-        let bounds = collider.bounds().expand(Vec2::new(
-            controller.skin_width * -2.0,
-            controller.skin_width * -2.0,
-        ));
-        let top_left = Vec2::new(
-            bounds.min().x + transform.translation.x,
-            bounds.max().y + transform.translation.y,
-        );
-        let top_right = Vec2::new(
-            bounds.max().x + transform.translation.x,
-            bounds.max().y + transform.translation.y,
-        );
-        let bottom_left = Vec2::new(
-            bounds.min().x + transform.translation.x,
-            bounds.min().y + transform.translation.y,
-        );
-        let bottom_right = Vec2::new(
-            bounds.max().x + transform.translation.x,
-            bounds.min().y + transform.translation.y,
-        );
+    for (mut ray_origins, controller, transform, collider, gravity) in query.iter_mut() {
+        let (min, max) = gravity.local_bounds(world_bounds(collider, transform));
+        let min = min + Vec2::splat(controller.skin_width);
+        let max = max - Vec2::splat(controller.skin_width);
+        let top_left = Vec2::new(min.x, max.y);
+        let top_right = max;
+        let bottom_left = min;
+        let bottom_right = Vec2::new(max.x, min.y);
 
         ray_origins.top_left = top_left;
         ray_origins.top_right = top_right;
@@ -154,8 +268,10 @@ fn update_ray_origins(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn horizontal_collisions(
     mut commands: Commands,
+    time: Res<Time>,
     mut controllers: Query<(
         Entity,
         &mut PlayerVelocity,
@@ -163,8 +279,13 @@ fn horizontal_collisions(
         &CharacterController,
         &RaycastOrigins,
         &RaySpacing,
+        &Gravity,
     )>,
-    colliders: Query<(&CollisionShape, &Transform), Without<CharacterController>>,
+    colliders: Query<(Entity, &CollisionShape, &Transform), SolidFilter>,
+    bodies: Query<(Entity, &CollisionShape, &Transform, &CharacterController)>,
+    surfaces: Query<&SurfaceVelocity>,
+    pushables: Query<(&Pushable, &CollisionShape, &Transform), Without<CharacterController>>,
+    mut pushed_events: EventWriter<Pushed>,
     mut collision_events: EventWriter<CollisionEvent>,
     shapes: Query<Entity, With<DebugShapeHorizontal>>,
 ) {
@@ -172,9 +293,46 @@ fn horizontal_collisions(
         commands.entity(shape).despawn();
     }
 
-    for (entity, mut velocity, mut collisions, controller, raycast_origins, ray_spacing) in
-        controllers.iter_mut()
-    {
+    // Characters already resolved this pass are collided with where they are moving to
+    let mut offsets = HashMap::default();
+
+    for entity in resolution_order(controllers.iter().map(|(entity, ..)| entity)) {
+        let (
+            entity,
+            mut velocity,
+            mut collisions,
+            controller,
+            raycast_origins,
+            ray_spacing,
+            gravity,
+        ) = match controllers.get_mut(entity) {
+            Ok(controller) => controller,
+            Err(_) => continue,
+        };
+        let world = (
+            &colliders,
+            other_controllers(&bodies, &offsets, entity, controller),
+        );
+        let solids = Oriented::new(&world, gravity);
+        collisions.stepped = false;
+        collisions.pushing = None;
+
+        // Get carried along by moving surfaces being stood on or pressed against
+        let ground_velocity = collisions
+            .ground
+            .filter(|_| collisions.below)
+            .and_then(|ground| surfaces.get(ground).ok())
+            .map(|surface| surface.0.x)
+            .unwrap_or_default();
+        let wall_velocity = collisions
+            .wall
+            .filter(|_| collisions.left || collisions.right)
+            .and_then(|wall| surfaces.get(wall).ok())
+            .map(|surface| surface.0.y)
+            .unwrap_or_default();
+        collisions.carry = Vec2::new(ground_velocity, wall_velocity) * time.delta_seconds();
+        velocity.0 += collisions.carry;
+
         if velocity.0.x == 0.0 {
             continue;
         }
@@ -183,26 +341,27 @@ fn horizontal_collisions(
         let mut right = false;
 
         let direction_x = velocity.0.x.signum();
-        let mut ray_length = velocity.0.x.abs() + controller.skin_width;
+        let ray_length = velocity.0.x.abs() + controller.skin_width;
+        let ray_direction = Vec2::X * direction_x;
+        let bottom = if direction_x == -1.0 {
+            raycast_origins.bottom_left
+        } else {
+            raycast_origins.bottom_right
+        };
+
+        let mut hits = Vec::with_capacity(controller.horizontal_ray_count as usize);
         for i in 0..controller.horizontal_ray_count {
-            let mut ray_origin = if direction_x == -1.0 {
-                raycast_origins.bottom_left
-            } else {
-                raycast_origins.bottom_right
-            };
-            ray_origin += Vec2::Y * (ray_spacing.horizontal * i as f32);
-            let ray_direction = Vec2::X * direction_x;
+            let ray_origin = bottom + Vec2::Y * (ray_spacing.horizontal * i as f32);
 
-            let hit =
-                colliders.ray_cast(Ray::new(ray_origin, ray_direction).with_length(ray_length));
+            let hit = solids.ray_cast(Ray::new(ray_origin, ray_direction).with_length(ray_length));
 
             // Ray cast line
             #[cfg(feature = "debug")]
             commands
                 .spawn_bundle(GeometryBuilder::build_as(
                     &shapes::Line(
-                        ray_origin + velocity.0,
-                        (ray_origin + (ray_direction * ray_length)) + velocity.0,
+                        gravity.to_world(ray_origin + velocity.0),
+                        gravity.to_world(ray_origin + ray_direction * ray_length + velocity.0),
                     ),
                     DrawMode::Stroke(StrokeMode {
                         color: Color::YELLOW,
@@ -212,13 +371,13 @@ fn horizontal_collisions(
                 ))
                 .insert(DebugShapeHorizontal);
 
+            // Point
+            #[cfg(feature = "debug")]
             if let Some(hit) = hit {
-                // Point
-                #[cfg(feature = "debug")]
                 commands
                     .spawn_bundle(GeometryBuilder::build_as(
                         &shapes::Circle {
-                            center: hit.position,
+                            center: gravity.to_world(hit.position),
                             radius: 1.0,
                         },
                         DrawMode::Stroke(StrokeMode {
@@ -228,9 +387,89 @@ fn horizontal_collisions(
                         Transform::from_xyz(0.0, 0.0, 10.0),
                     ))
                     .insert(DebugShapeHorizontal);
+            }
+
+            hits.push(hit);
+        }
 
-                velocity.0.x = (hit.distance - controller.skin_width) * direction_x;
-                ray_length = hit.distance;
+        // Catch colliders small enough to pass between the rays
+        let swept = swept_hit(controller, &hits, || {
+            box_cast(
+                &colliders,
+                &world.1,
+                gravity,
+                (raycast_origins.bottom_left, raycast_origins.top_right),
+                ray_direction,
+                ray_length,
+            )
+        });
+
+        // Step up onto small obstacles, or hop over ledges clipped while airborne
+        let max_lift = if collisions.below {
+            controller.step_height
+        } else {
+            controller.corner_correction
+        };
+        let lift = if swept.is_some() {
+            None
+        } else {
+            obstacle_lift(
+                &solids,
+                &hits,
+                raycast_origins,
+                ray_spacing.horizontal,
+                direction_x,
+                controller.skin_width,
+                max_lift,
+            )
+        };
+
+        if let Some(lift) = lift {
+            velocity.0.y = velocity.0.y.max(lift);
+            collisions.stepped = true;
+        } else if let Some(hit) = swept.or_else(|| closest_hit(&hits)) {
+            let mut distance = hit.distance - controller.skin_width;
+
+            // Push pushables along instead of stopping, unless something else is in the way
+            let pushable = hit.entity.and_then(|pushed| {
+                pushables
+                    .get(pushed)
+                    .ok()
+                    .map(|pushable| (pushed, pushable))
+            });
+            if let Some((pushed, (pushable, pushed_collider, pushed_transform))) = pushable {
+                let others: Vec<_> = hits
+                    .iter()
+                    .map(|other| other.filter(|other| other.entity != Some(pushed)))
+                    .collect();
+                let max_distance = closest_hit(&others).map_or(f32::INFINITY, |other| {
+                    other.distance - controller.skin_width
+                });
+
+                let bounds = gravity.local_bounds(world_bounds(pushed_collider, pushed_transform));
+                let push = push_distance(
+                    &Oriented::new(&Excluding::new(&colliders, pushed), gravity),
+                    bounds,
+                    direction_x,
+                    (velocity.0.x.abs() - distance) * pushable.speed_multiplier,
+                )
+                .min(max_distance - distance);
+
+                if push > 0.0 {
+                    distance += push;
+                    collisions.pushing = Some(pushed);
+                    pushed_events.send(Pushed {
+                        entity: pushed,
+                        pusher: entity,
+                        offset: gravity.to_world(Vec2::X * direction_x * push),
+                    });
+                }
+            }
+
+            velocity.0.x = distance * direction_x;
+
+            if collisions.pushing.is_none() {
+                collisions.wall = hit.entity;
 
                 if direction_x == -1.0 {
                     left = true;
@@ -241,6 +480,34 @@ fn horizontal_collisions(
             }
         }
 
+        // Moving diagonally can run into the side of a box above or below the horizontal rays,
+        // which stops horizontal movement where it touches
+        if lift.is_none() && collisions.pushing.is_none() {
+            let direction_y = if velocity.0.y > 0.0 { 1.0 } else { -1.0 };
+            let side = diagonal_hit(
+                &solids,
+                &leading_origins(
+                    raycast_origins,
+                    ray_spacing,
+                    controller,
+                    direction_x,
+                    direction_y,
+                ),
+                velocity.0,
+                controller.skin_width,
+            )
+            .filter(|(hit, offset)| {
+                hit.normal.x.abs() > hit.normal.y.abs() && offset.x.abs() < velocity.0.x.abs()
+            });
+            if let Some((hit, offset)) = side {
+                velocity.0.x = offset.x;
+                collisions.wall = hit.entity;
+                left = direction_x == -1.0;
+                right = direction_x == 1.0;
+            }
+        }
+        offsets.insert(entity, gravity.to_world(Vec2::X * velocity.0.x));
+
         if collisions.left != left {
             collisions.left = left;
             collision_events.send(CollisionEvent {
@@ -267,8 +534,15 @@ fn vertical_collisions(
         &CharacterController,
         &RaycastOrigins,
         &RaySpacing,
+        &Climb,
+        &Gravity,
     )>,
-    colliders: Query<(&CollisionShape, &Transform), Without<CharacterController>>,
+    colliders: Query<(Entity, &CollisionShape, &Transform), SolidFilter>,
+    bodies: Query<(Entity, &CollisionShape, &Transform, &CharacterController)>,
+    climbables: Query<
+        (Entity, &CollisionShape, &Transform),
+        (With<Climbable>, Without<CharacterController>),
+    >,
     mut collision_events: EventWriter<CollisionEvent>,
     shapes: Query<Entity, With<DebugShapeVertical>>,
 ) {
@@ -276,14 +550,63 @@ fn vertical_collisions(
         commands.entity(shape).despawn();
     }
 
-    for (entity, mut velocity, mut collisions, controller, raycast_origins, ray_spacing) in
-        controllers.iter_mut()
-    {
+    // Every character has already moved horizontally, and those resolved this pass vertically too
+    let mut offsets: HashMap<_, _> = controllers
+        .iter()
+        .map(|(entity, velocity, .., gravity)| (entity, gravity.to_world(Vec2::X * velocity.0.x)))
+        .collect();
+
+    for entity in resolution_order(controllers.iter().map(|(entity, ..)| entity)) {
+        let (
+            entity,
+            mut velocity,
+            mut collisions,
+            controller,
+            raycast_origins,
+            ray_spacing,
+            climb,
+            gravity,
+        ) = match controllers.get_mut(entity) {
+            Ok(controller) => controller,
+            Err(_) => continue,
+        };
+        let world = (
+            &colliders,
+            other_controllers(&bodies, &offsets, entity, controller),
+        );
+        let solids = Oriented::new(&world, gravity);
         let mut below = false;
         let mut above = false;
+        let mut ground = None;
+
+        // Probe the ground when not moving vertically, so moving along it (such as a ground dash)
+        // stays grounded
+        let direction_y = if velocity.0.y > 0.0 { 1.0 } else { -1.0 };
+        let ray_length = velocity.0.y.abs() + controller.skin_width;
+        let ray_direction = Vec2::Y * direction_y;
+
+        // Rays cast after moving horizontally pass box corners between the start and end of a
+        // diagonal move, so sweep the leading sides along the diagonal first
+        let diagonal = if collisions.stepped || collisions.pushing.is_some() {
+            None
+        } else {
+            diagonal_hit(
+                &solids,
+                &leading_origins(
+                    raycast_origins,
+                    ray_spacing,
+                    controller,
+                    velocity.0.x.signum(),
+                    direction_y,
+                ),
+                velocity.0,
+                controller.skin_width,
+            )
+        };
+        // Running into the side of a box was already resolved by the horizontal pass
+        let corner = diagonal.filter(|(hit, _)| hit.normal.y.abs() >= hit.normal.x.abs());
 
-        let direction_y = velocity.0.y.signum();
-        let mut ray_length = velocity.0.y.abs() + controller.skin_width;
+        let mut hits = Vec::with_capacity(controller.vertical_ray_count as usize);
         for i in 0..controller.vertical_ray_count {
             let mut ray_origin = if direction_y == -1.0 {
                 raycast_origins.bottom_left
@@ -291,18 +614,23 @@ fn vertical_collisions(
                 raycast_origins.top_left
             };
             ray_origin += Vec2::X * (ray_spacing.vertical * i as f32 + velocity.0.x);
-            let ray_direction = Vec2::Y * direction_y;
 
-            let hit =
-                colliders.ray_cast(Ray::new(ray_origin, ray_direction).with_length(ray_length));
+            let mut hit =
+                solids.ray_cast(Ray::new(ray_origin, ray_direction).with_length(ray_length));
+
+            // The top of a ladder can be stood on unless climbing down it
+            if direction_y == -1.0 && !climb.is_climbing() {
+                let platform_hit = platform_hit(&climbables, gravity, ray_origin, ray_length);
+                hit = closest_hit(&[hit, platform_hit]);
+            }
 
             // Ray cast line
             #[cfg(feature = "debug")]
             commands
                 .spawn_bundle(GeometryBuilder::build_as(
                     &shapes::Line(
-                        ray_origin + velocity.0,
-                        (ray_origin + (ray_direction * ray_length)) + velocity.0,
+                        gravity.to_world(ray_origin + velocity.0),
+                        gravity.to_world(ray_origin + ray_direction * ray_length + velocity.0),
                     ),
                     DrawMode::Stroke(StrokeMode {
                         color: Color::YELLOW,
@@ -312,13 +640,13 @@ fn vertical_collisions(
                 ))
                 .insert(DebugShapeVertical);
 
+            // Point
+            #[cfg(feature = "debug")]
             if let Some(hit) = hit {
-                // Point
-                #[cfg(feature = "debug")]
                 commands
                     .spawn_bundle(GeometryBuilder::build_as(
                         &shapes::Circle {
-                            center: hit.position,
+                            center: gravity.to_world(hit.position),
                             radius: 1.0,
                         },
                         DrawMode::Stroke(StrokeMode {
@@ -328,19 +656,78 @@ fn vertical_collisions(
                         Transform::from_xyz(0.0, 0.0, 100.0),
                     ))
                     .insert(DebugShapeVertical);
+            }
 
-                velocity.0.y = (hit.distance - controller.skin_width) * direction_y;
-                ray_length = hit.distance;
+            hits.push(hit);
+        }
 
-                if direction_y == -1.0 {
-                    below = true;
-                }
-                if direction_y == 1.0 {
-                    above = true;
-                }
+        let swept = swept_hit(controller, &hits, || {
+            box_cast(
+                &colliders,
+                &world.1,
+                gravity,
+                (
+                    raycast_origins.bottom_left + Vec2::X * velocity.0.x,
+                    raycast_origins.top_right + Vec2::X * velocity.0.x,
+                ),
+                ray_direction,
+                ray_length,
+            )
+        });
+
+        // Landing on or bumping into a corner before the end of the move stops it where it touches
+        let hit = swept.or_else(|| closest_hit(&hits));
+        let corner = corner.filter(|(_, offset)| {
+            hit.map_or(true, |hit| {
+                offset.y.abs() < hit.distance - controller.skin_width - DIAGONAL_CONTACT_TOLERANCE
+            })
+        });
+
+        // Slide around ceiling corners clipped by only the outermost rays
+        let nudge = if direction_y == 1.0 && swept.is_none() && corner.is_none() {
+            corner_nudge(
+                &solids,
+                &hits,
+                raycast_origins.top_left + Vec2::X * velocity.0.x,
+                raycast_origins.top_right + Vec2::X * velocity.0.x,
+                ray_spacing.vertical,
+                ray_length,
+                controller.skin_width,
+                controller.corner_correction,
+            )
+        } else {
+            None
+        };
+
+        let contact = corner.or_else(|| {
+            hit.map(|hit| {
+                let offset_y = (hit.distance - controller.skin_width) * direction_y;
+                (hit, Vec2::new(velocity.0.x, offset_y))
+            })
+        });
+
+        // A nudged jump keeps its upward velocity
+        if let Some(nudge) = nudge {
+            velocity.0.x += nudge;
+        } else if let Some((hit, offset)) = contact {
+            velocity.0 = offset;
+
+            if direction_y == -1.0 {
+                below = true;
+                ground = hit.entity;
+            }
+            if direction_y == 1.0 {
+                above = true;
             }
         }
 
+        // Stepping up or hopping onto a ledge leaves the character standing on it
+        if collisions.stepped && !above {
+            below = true;
+            ground = collisions.ground;
+        }
+        collisions.ground = ground;
+
         if collisions.below != below {
             collisions.below = below;
             collision_events.send(CollisionEvent {
@@ -355,6 +742,88 @@ fn vertical_collisions(
                 side: CollisionSide::Above(above),
             });
         }
+
+        offsets.insert(entity, gravity.to_world(velocity.0));
+    }
+}
+
+/// Splits fast movement of swept characters into steps no longer than
+/// [`CharacterController::max_step_distance`], moving horizontally then vertically each step, so
+/// diagonal movement follows the colliders it passes instead of cutting across them.
+///
+/// Once a step on an axis would hit something, that step is kept and the rest of the movement on
+/// that axis is dropped. The collision passes then clamp it against the obstacle, casting from the
+/// character's starting position rather than along the stepped path.
+///
+/// Sub-stepping relies on [`box_cast`], so it only applies with [`CollisionMode::Swept`]. Ray based
+/// characters always move in a single step, however fast they go.
+fn sweep(
+    mut controllers: Query<(
+        Entity,
+        &mut PlayerVelocity,
+        &CharacterController,
+        &RaycastOrigins,
+        &Gravity,
+    )>,
+    colliders: Query<(Entity, &CollisionShape, &Transform), SolidFilter>,
+    bodies: Query<(Entity, &CollisionShape, &Transform, &CharacterController)>,
+) {
+    for (entity, mut velocity, controller, raycast_origins, gravity) in controllers.iter_mut() {
+        if controller.collision_mode != CollisionMode::Swept
+            || controller.max_step_distance <= 0.0
+            || velocity.0.length() <= controller.max_step_distance
+        {
+            continue;
+        }
+
+        let others = other_controllers(&bodies, &HashMap::default(), entity, controller);
+        let steps = (velocity.0.length() / controller.max_step_distance).ceil();
+        let step = velocity.0 / steps;
+        let mut travelled = Vec2::ZERO;
+        let mut blocked = [false; 2];
+
+        for _ in 0..steps as u32 {
+            for (axis, blocked) in [Vec2::X, Vec2::Y].into_iter().zip(blocked.iter_mut()) {
+                let distance = step.dot(axis);
+                if *blocked || distance == 0.0 {
+                    continue;
+                }
+
+                let hit = box_cast(
+                    &colliders,
+                    &others,
+                    gravity,
+                    (
+                        raycast_origins.bottom_left + travelled,
+                        raycast_origins.top_right + travelled,
+                    ),
+                    axis * distance.signum(),
+                    distance.abs() + controller.skin_width,
+                );
+                *blocked = hit.is_some();
+                travelled += axis * distance;
+            }
+        }
+
+        velocity.0 = travelled;
+    }
+}
+
+/// Removes movement which only applied for a single frame, such as stepping up onto a ledge or
+/// being carried along by a moving surface, so it doesn't build up over time.
+fn restore_velocity(mut query: Query<(&mut PlayerVelocity, &CollisionInfo)>) {
+    for (mut velocity, collisions) in query.iter_mut() {
+        // Movement cut short by a collision no longer includes the carry
+        if !collisions.left && !collisions.right {
+            velocity.0.x -= collisions.carry.x;
+        }
+        if !collisions.below && !collisions.above {
+            velocity.0.y -= collisions.carry.y;
+        }
+
+        if collisions.stepped {
+            velocity.0.y = 0.0;
+        }
     }
 }
 
@@ -401,3 +870,580 @@ fn coyote_time(
         coyote_stopwatch.0.tick(time.delta());
     }
 }
+
+/// World space bounding box of a collider, as `(min, max)`.
+pub(crate) fn world_bounds(collider: &CollisionShape, transform: &Transform) -> (Vec2, Vec2) {
+    let bounds = collider.bounds();
+    let offset = transform.translation.truncate();
+    (
+        Vec2::new(bounds.min().x, bounds.min().y) + offset,
+        Vec2::new(bounds.max().x, bounds.max().y) + offset,
+    )
+}
+
+pub(crate) fn bounds_overlap((min_a, max_a): (Vec2, Vec2), (min_b, max_b): (Vec2, Vec2)) -> bool {
+    min_a.x < max_b.x && max_a.x > min_b.x && min_a.y < max_b.y && max_a.y > min_b.y
+}
+
+/// Casts a ray downwards against only the top edges of colliders below its origin, so they can
+/// be landed on but passed through from below or the sides.
+///
+/// The ray and hit are relative to `gravity`.
+fn platform_hit(
+    platforms: &Query<
+        (Entity, &CollisionShape, &Transform),
+        (With<Climbable>, Without<CharacterController>),
+    >,
+    gravity: &Gravity,
+    ray_origin: Vec2,
+    ray_length: f32,
+) -> Option<RayHit> {
+    let ray = Ray::new(ray_origin, -Vec2::Y).with_length(ray_length);
+    let hits: Vec<_> = platforms
+        .iter()
+        .map(|(entity, collider, transform)| {
+            let (min, max) = gravity.local_bounds(world_bounds(collider, transform));
+            if ray_origin.y < max.y {
+                return None;
+            }
+            Edge(Vec2::new(min.x, max.y), Vec2::new(max.x, max.y))
+                .ray_cast(ray)
+                .map(|hit| RayHit {
+                    entity: Some(entity),
+                    ..hit
+                })
+        })
+        .collect();
+    closest_hit(&hits)
+}
+
+/// Controllers sorted by entity, so collisions between characters resolve the same way every
+/// frame regardless of query order.
+fn resolution_order(entities: impl Iterator<Item = Entity>) -> Vec<Entity> {
+    let mut order: Vec<_> = entities.collect();
+    order.sort();
+    order
+}
+
+/// World space bounds of other characters `entity` collides with, moved by their `offsets`.
+fn other_controllers(
+    bodies: &Query<(Entity, &CollisionShape, &Transform, &CharacterController)>,
+    offsets: &HashMap<Entity, Vec2>,
+    entity: Entity,
+    controller: &CharacterController,
+) -> Boxes {
+    if !controller.collide_with_controllers {
+        return Boxes(Vec::new());
+    }
+
+    Boxes(
+        bodies
+            .iter()
+            .filter(|(other, _, _, other_controller)| {
+                *other != entity && other_controller.collide_with_controllers
+            })
+            .map(|(other, collider, transform, _)| {
+                let offset = offsets.get(&other).copied().unwrap_or_default();
+                let (min, max) = world_bounds(collider, transform);
+                (other, min + offset, max + offset)
+            })
+            .collect(),
+    )
+}
+
+fn closest_hit(hits: &[Option<RayHit>]) -> Option<RayHit> {
+    hits.iter()
+        .flatten()
+        .copied()
+        .fold(None, |closest: Option<RayHit>, hit| match closest {
+            Some(closest) if closest.distance <= hit.distance => Some(closest),
+            _ => Some(hit),
+        })
+}
+
+/// Hit from sweeping the whole shape of a character with [`CollisionMode::Swept`], if it is closer
+/// than any of the ray `hits`.
+fn swept_hit(
+    controller: &CharacterController,
+    hits: &[Option<RayHit>],
+    cast: impl FnOnce() -> Option<RayHit>,
+) -> Option<RayHit> {
+    if controller.collision_mode != CollisionMode::Swept {
+        return None;
+    }
+
+    let closest = closest_hit(hits);
+    cast().filter(|hit| closest.map_or(true, |closest| hit.distance < closest.distance))
+}
+
+/// Casts a box with `bounds`, as `(min, max)` relative to `gravity`, along `direction` against
+/// solid colliders and `others`.
+///
+/// The box is cast as a ray from its center against every collider grown by its half extents.
+/// The hit distance is from the side of the box facing `direction`.
+fn box_cast(
+    colliders: &Query<(Entity, &CollisionShape, &Transform), SolidFilter>,
+    others: &Boxes,
+    gravity: &Gravity,
+    (min, max): (Vec2, Vec2),
+    direction: Vec2,
+    length: f32,
+) -> Option<RayHit> {
+    let half_extents = (max - min) / 2.0;
+    let grown = Boxes(
+        colliders
+            .iter()
+            .map(|(entity, collider, transform)| (entity, world_bounds(collider, transform)))
+            .chain(
+                others
+                    .0
+                    .iter()
+                    .map(|(entity, min, max)| (*entity, (*min, *max))),
+            )
+            .map(|(entity, bounds)| {
+                let (min, max) = gravity.local_bounds(bounds);
+                (entity, min - half_extents, max + half_extents)
+            })
+            .collect(),
+    );
+    grown.ray_cast(Ray::new((min + max) / 2.0, direction).with_length(length))
+}
+
+/// Origins of the rays on the sides of a character leading a move along `direction_x` and
+/// `direction_y`.
+fn leading_origins(
+    raycast_origins: &RaycastOrigins,
+    ray_spacing: &RaySpacing,
+    controller: &CharacterController,
+    direction_x: f32,
+    direction_y: f32,
+) -> Vec<Vec2> {
+    let (row, corner) = match (direction_x == -1.0, direction_y == -1.0) {
+        (true, true) => (raycast_origins.bottom_left, raycast_origins.bottom_left),
+        (false, true) => (raycast_origins.bottom_left, raycast_origins.bottom_right),
+        (true, false) => (raycast_origins.top_left, raycast_origins.top_left),
+        (false, false) => (raycast_origins.top_left, raycast_origins.top_right),
+    };
+
+    // The column runs away from the leading corner, which is already in the row
+    (0..controller.vertical_ray_count)
+        .map(|i| row + Vec2::X * (ray_spacing.vertical * i as f32))
+        .chain(
+            (1..controller.horizontal_ray_count)
+                .map(|i| corner - Vec2::Y * direction_y * (ray_spacing.horizontal * i as f32)),
+        )
+        .collect()
+}
+
+/// First collider touched when moving along the whole diagonal `velocity` from `origins`, instead
+/// of horizontally then vertically, and the movement up to where the skin touches it.
+///
+/// Resolving horizontal then vertical movement traces an L-shaped path, which cuts past box
+/// corners lying between the start and end of a diagonal move.
+fn diagonal_hit(
+    colliders: &impl RayCast,
+    origins: &[Vec2],
+    velocity: Vec2,
+    skin_width: f32,
+) -> Option<(RayHit, Vec2)> {
+    if velocity.x == 0.0 || velocity.y == 0.0 {
+        return None;
+    }
+
+    let direction = velocity.normalize();
+    let length = velocity.length();
+    origins
+        .iter()
+        .filter_map(|origin| {
+            let hit = colliders
+                .ray_cast(Ray::new(*origin, direction).with_length(length + skin_width))?;
+            // The skin is measured along the normal of the face that was hit
+            let gap = (hit.position - *origin).dot(hit.normal).abs();
+            let travel = ((gap - skin_width) / direction.dot(hit.normal).abs()).max(0.0);
+            (travel < length).then_some((hit, travel))
+        })
+        .fold(
+            None,
+            |closest: Option<(RayHit, f32)>, (hit, travel)| match closest {
+                Some(closest) if closest.1 <= travel => Some(closest),
+                _ => Some((hit, travel)),
+            },
+        )
+        .map(|(hit, travel)| (hit, direction * travel))
+}
+
+/// Height to lift a character moving horizontally so it clears an obstacle hit only by its lowest
+/// rays, if the top of the obstacle is within `max_lift` of its feet.
+fn obstacle_lift(
+    colliders: &impl RayCast,
+    hits: &[Option<RayHit>],
+    raycast_origins: &RaycastOrigins,
+    ray_spacing: f32,
+    direction_x: f32,
+    skin_width: f32,
+    max_lift: f32,
+) -> Option<f32> {
+    if max_lift <= 0.0 {
+        return None;
+    }
+
+    // Rays higher than the maximum lift must all be clear
+    let too_high = hits
+        .iter()
+        .enumerate()
+        .any(|(i, hit)| hit.is_some() && skin_width + ray_spacing * i as f32 > max_lift);
+    if too_high {
+        return None;
+    }
+    let hit = closest_hit(hits)?;
+
+    // Find the top of the obstacle just inside its face
+    let feet = raycast_origins.bottom_left.y - skin_width;
+    let probe = Vec2::new(hit.position.x + direction_x * skin_width, feet + max_lift);
+    let top = colliders.ray_cast(Ray::new(probe, -Vec2::Y).with_length(max_lift))?;
+    let lift = top.position.y - feet;
+    if lift <= 0.0 || lift > max_lift {
+        return None;
+    }
+
+    // Make sure there is headroom to be lifted into
+    let headroom = skin_width + lift;
+    let blocked = [raycast_origins.top_left, raycast_origins.top_right]
+        .into_iter()
+        .any(|origin| {
+            colliders
+                .ray_cast(Ray::new(origin, Vec2::Y).with_length(headroom))
+                .is_some()
+        });
+    if blocked {
+        return None;
+    }
+
+    Some(lift)
+}
+
+/// Horizontal distance to nudge a character moving upwards so it slides past a ceiling corner hit
+/// only by its outermost rays, if the overlap is within `max_nudge`.
+#[allow(clippy::too_many_arguments)]
+fn corner_nudge(
+    colliders: &impl RayCast,
+    hits: &[Option<RayHit>],
+    top_left: Vec2,
+    top_right: Vec2,
+    ray_spacing: f32,
+    ray_length: f32,
+    skin_width: f32,
+    max_nudge: f32,
+) -> Option<f32> {
+    if max_nudge <= 0.0 || hits.len() < 2 {
+        return None;
+    }
+
+    let first_hit = hits.iter().position(Option::is_some)?;
+    let last_hit = hits.iter().rposition(Option::is_some)?;
+    let hit = closest_hit(hits)?;
+
+    // Probe sideways from the first clear ray towards the corner that was clipped
+    let (direction_x, clear_origin, edge) = if last_hit * 2 < hits.len() - 1 {
+        (
+            1.0,
+            top_left + Vec2::X * (ray_spacing * (last_hit + 1) as f32),
+            top_left.x,
+        )
+    } else if first_hit * 2 > hits.len() - 1 {
+        let clear = top_left + Vec2::X * (ray_spacing * (first_hit - 1) as f32);
+        (-1.0, clear, top_right.x)
+    } else {
+        return None;
+    };
+
+    let probe = Vec2::new(clear_origin.x, hit.position.y + CORNER_PROBE_INSET);
+    let side = colliders.ray_cast(
+        Ray::new(probe, Vec2::X * -direction_x)
+            .with_length((clear_origin.x - edge).abs() + skin_width),
+    )?;
+    let nudge = (side.position.x - (edge - direction_x * skin_width)) * direction_x;
+    if nudge <= 0.0 || nudge > max_nudge {
+        return None;
+    }
+
+    // Make sure the nudged character has room to keep moving
+    let leading_corner = if direction_x == 1.0 {
+        top_right
+    } else {
+        top_left
+    };
+    let wall = colliders
+        .ray_cast(Ray::new(leading_corner, Vec2::X * direction_x).with_length(nudge + skin_width));
+    let ceiling = colliders.ray_cast(
+        Ray::new(leading_corner + Vec2::X * direction_x * nudge, Vec2::Y).with_length(ray_length),
+    );
+    if wall.is_some() || ceiling.is_some() {
+        return None;
+    }
+
+    Some(nudge * direction_x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKIN_WIDTH: f32 = 1.0;
+
+    fn boxes(bounds: &[(Vec2, Vec2)]) -> Boxes {
+        Boxes(
+            bounds
+                .iter()
+                .enumerate()
+                .map(|(i, (min, max))| (Entity::from_raw(i as u32), *min, *max))
+                .collect(),
+        )
+    }
+
+    /// Ray origins for a 10x20 character standing with its feet at the origin.
+    fn raycast_origins() -> RaycastOrigins {
+        RaycastOrigins {
+            top_left: Vec2::new(1.0, 19.0),
+            top_right: Vec2::new(9.0, 19.0),
+            bottom_left: Vec2::new(1.0, 1.0),
+            bottom_right: Vec2::new(9.0, 1.0),
+        }
+    }
+
+    fn bottom_origins() -> Vec<Vec2> {
+        let origins = raycast_origins();
+        (0..4)
+            .map(|i| origins.bottom_left + Vec2::X * (8.0 / 3.0 * i as f32))
+            .collect()
+    }
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 1e-4, "{} != {}", a, b);
+    }
+
+    /// Spawns a 10x20 character with its feet at `feet`, moving by `velocity` this frame.
+    fn spawn_character(world: &mut World, feet: Vec2, velocity: Vec2) -> Entity {
+        world
+            .spawn()
+            .insert_bundle((
+                CharacterController::default(),
+                CollisionShape::new_rectangle(10.0, 20.0),
+                Transform::from_translation((feet + Vec2::new(5.0, 10.0)).extend(0.0)),
+                PlayerVelocity(velocity),
+                CollisionInfo::default(),
+                RaycastOrigins::default(),
+                RaySpacing::default(),
+                Climb::default(),
+                Gravity::default(),
+            ))
+            .id()
+    }
+
+    fn spawn_box(world: &mut World, min: Vec2, max: Vec2) {
+        let size = max - min;
+        world.spawn().insert_bundle((
+            CollisionShape::new_rectangle(size.x, size.y),
+            Transform::from_translation(((min + max) / 2.0).extend(0.0)),
+        ));
+    }
+
+    /// Runs the collision passes once, in the same order as [`CollisionsPlugin`].
+    fn run_collisions(world: &mut World) {
+        world.insert_resource(Time::default());
+        world.insert_resource(Events::<Pushed>::default());
+        world.insert_resource(Events::<CollisionEvent>::default());
+
+        let mut stage = SystemStage::single_threaded();
+        stage
+            .add_system(calculate_ray_spacing.label(ControllerLabel::Calculate))
+            .add_system(update_ray_origins.label(ControllerLabel::Calculate))
+            .add_system(
+                horizontal_collisions
+                    .label(ControllerLabel::HorizontalCollisions)
+                    .after(ControllerLabel::Calculate),
+            )
+            .add_system(
+                vertical_collisions
+                    .after(ControllerLabel::Calculate)
+                    .after(ControllerLabel::HorizontalCollisions),
+            );
+        stage.run(world);
+    }
+
+    #[test]
+    fn corner_nudge_slides_past_ceiling_corner() {
+        let colliders = boxes(&[(Vec2::new(-10.0, 22.0), Vec2::new(2.0, 30.0))]);
+        let origins = raycast_origins();
+        let ray_spacing = 8.0 / 3.0;
+        let ray_length = 5.0 + SKIN_WIDTH;
+        let hits: Vec<_> = (0..4)
+            .map(|i| {
+                let origin = origins.top_left + Vec2::X * (ray_spacing * i as f32);
+                colliders.ray_cast(Ray::new(origin, Vec2::Y).with_length(ray_length))
+            })
+            .collect();
+
+        let nudge = |max_nudge| {
+            corner_nudge(
+                &colliders,
+                &hits,
+                origins.top_left,
+                origins.top_right,
+                ray_spacing,
+                ray_length,
+                SKIN_WIDTH,
+                max_nudge,
+            )
+        };
+        assert!((nudge(4.0).unwrap() - 2.0).abs() < 1e-4);
+        assert!(nudge(1.0).is_none());
+    }
+
+    #[test]
+    fn obstacle_lift_steps_onto_low_obstacle() {
+        let colliders = boxes(&[(Vec2::new(12.0, -10.0), Vec2::new(20.0, 3.0))]);
+        let origins = raycast_origins();
+        let ray_spacing = 18.0 / 5.0;
+        let hits: Vec<_> = (0..6)
+            .map(|i| {
+                let origin = origins.bottom_right + Vec2::Y * (ray_spacing * i as f32);
+                colliders.ray_cast(Ray::new(origin, Vec2::X).with_length(5.0 + SKIN_WIDTH))
+            })
+            .collect();
+
+        let lift = |max_lift| {
+            obstacle_lift(
+                &colliders,
+                &hits,
+                &origins,
+                ray_spacing,
+                1.0,
+                SKIN_WIDTH,
+                max_lift,
+            )
+        };
+        assert!((lift(4.0).unwrap() - 3.0).abs() < 1e-4);
+        assert!(lift(2.0).is_none());
+        assert!(lift(0.0).is_none());
+    }
+
+    #[test]
+    fn minimum_translation_pushes_out_along_shallowest_axis() {
+        let a = (Vec2::ZERO, Vec2::splat(10.0));
+        assert_eq!(
+            minimum_translation(a, (Vec2::new(8.0, 2.0), Vec2::splat(20.0))),
+            Some(Vec2::new(-2.0, 0.0))
+        );
+        assert_eq!(
+            minimum_translation(a, (Vec2::splat(-5.0), Vec2::new(15.0, 3.0))),
+            Some(Vec2::new(0.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn minimum_translation_ignores_touching_bounds() {
+        let a = (Vec2::ZERO, Vec2::splat(10.0));
+        assert_eq!(
+            minimum_translation(a, (Vec2::new(10.0, 0.0), Vec2::new(20.0, 10.0))),
+            None
+        );
+    }
+
+    #[test]
+    fn diagonal_hit_lands_on_corner_skipped_by_separate_passes() {
+        // Moving right then down ends past the box, but the diagonal passes over its corner
+        let colliders = boxes(&[(Vec2::new(12.0, -30.0), Vec2::new(16.0, -5.0))]);
+        let (hit, offset) = diagonal_hit(
+            &colliders,
+            &bottom_origins(),
+            Vec2::new(20.0, -20.0),
+            SKIN_WIDTH,
+        )
+        .unwrap();
+
+        assert_eq!(hit.normal, Vec2::Y);
+        assert_close(offset, Vec2::new(5.0, -5.0));
+    }
+
+    #[test]
+    fn diagonal_hit_stops_at_side_of_box() {
+        let colliders = boxes(&[(Vec2::new(12.0, -40.0), Vec2::new(16.0, -1.0))]);
+        let (hit, offset) = diagonal_hit(
+            &colliders,
+            &bottom_origins(),
+            Vec2::new(20.0, -20.0),
+            SKIN_WIDTH,
+        )
+        .unwrap();
+
+        assert_eq!(hit.normal, -Vec2::X);
+        assert_close(offset, Vec2::new(2.0, -2.0));
+    }
+
+    #[test]
+    fn diagonal_hit_ignores_straight_movement_and_distant_boxes() {
+        let colliders = boxes(&[(Vec2::new(12.0, -30.0), Vec2::new(16.0, -5.0))]);
+        let origins = bottom_origins();
+
+        assert!(diagonal_hit(&colliders, &origins, Vec2::new(20.0, 0.0), SKIN_WIDTH).is_none());
+        assert!(diagonal_hit(&colliders, &origins, Vec2::new(0.0, -20.0), SKIN_WIDTH).is_none());
+        assert!(diagonal_hit(&colliders, &origins, Vec2::new(2.0, -2.0), SKIN_WIDTH).is_none());
+    }
+
+    #[test]
+    fn vertical_pass_follows_horizontal_movement() {
+        // The floor starts past the character, so it's only landed on after moving right
+        let mut world = World::new();
+        spawn_box(&mut world, Vec2::new(12.0, -10.0), Vec2::new(40.0, 0.0));
+        let character = spawn_character(&mut world, Vec2::new(0.0, 2.0), Vec2::new(10.0, -5.0));
+
+        run_collisions(&mut world);
+
+        assert_close(
+            world.get::<PlayerVelocity>(character).unwrap().0,
+            Vec2::new(10.0, -2.0),
+        );
+        let collisions = world.get::<CollisionInfo>(character).unwrap();
+        assert!(collisions.below);
+        assert!(!collisions.right);
+    }
+
+    #[test]
+    fn nudged_jump_keeps_its_upward_velocity() {
+        let mut world = World::new();
+        spawn_box(&mut world, Vec2::new(-10.0, 22.0), Vec2::new(2.0, 30.0));
+        let character = spawn_character(&mut world, Vec2::ZERO, Vec2::new(0.0, 5.0));
+        world
+            .get_mut::<CharacterController>(character)
+            .unwrap()
+            .corner_correction = 4.0;
+
+        run_collisions(&mut world);
+
+        assert_close(
+            world.get::<PlayerVelocity>(character).unwrap().0,
+            Vec2::new(2.0, 5.0),
+        );
+        assert!(!world.get::<CollisionInfo>(character).unwrap().above);
+    }
+
+    #[test]
+    fn diagonal_move_into_side_of_box_touches_wall() {
+        // The box is below the horizontal rays, so only the diagonal runs into its side
+        let mut world = World::new();
+        spawn_box(&mut world, Vec2::new(12.0, -40.0), Vec2::new(16.0, -1.0));
+        let character = spawn_character(&mut world, Vec2::ZERO, Vec2::new(20.0, -20.0));
+
+        run_collisions(&mut world);
+
+        assert_close(
+            world.get::<PlayerVelocity>(character).unwrap().0,
+            Vec2::new(2.0, -20.0),
+        );
+        let collisions = world.get::<CollisionInfo>(character).unwrap();
+        assert!(collisions.right);
+        assert!(collisions.wall.is_some());
+        assert!(!collisions.below);
+    }
+}
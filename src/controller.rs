@@ -2,7 +2,7 @@ use bevy::prelude::*;
 #[cfg(feature = "debug")]
 use bevy_prototype_lyon::prelude::*;
 
-use crate::{collisions::PlayerVelocity, ControllerLabel};
+use crate::{collisions::PlayerVelocity, gravity::Gravity, ControllerLabel};
 
 pub(crate) struct ControllerPlugin;
 
@@ -31,12 +31,108 @@ pub struct CharacterController {
     pub acceleration_time_grounded: f32,
     /// Time in seconds to accelerate to move speed when airborne _(default: `0.2`)_
     pub acceleration_time_airborne: f32,
+    /// Time in seconds to slow down to a stop when grounded _(default: `0.1`)_
+    pub deceleration_time_grounded: f32,
+    /// Time in seconds to slow down to a stop when airborne _(default: `0.2`)_
+    pub deceleration_time_airborne: f32,
+    /// Time in seconds to reverse direction when grounded _(default: `0.1`)_
+    pub turnaround_time_grounded: f32,
+    /// Time in seconds to reverse direction when airborne _(default: `0.2`)_
+    pub turnaround_time_airborne: f32,
+    /// Easing used when changing horizontal speed _(default: `AccelerationCurve::SmoothDamp`)_
+    #[reflect(ignore)]
+    pub acceleration_curve: AccelerationCurve,
+    /// Direction gravity pulls in, snapped to the nearest axis _(default: `Vec2::new(0.0, -1.0)`)_
+    pub gravity_direction: Vec2,
     /// Gravity multiplier when jumpin up _(default: `1.0`)_
     pub gravity_up_multiplier: f32,
     /// Gravity multiplier when falling down _(default: `1.5`)_
     pub gravity_down_multiplier: f32,
+    /// Vertical speed in units per second below which a jump is considered at its apex _(default: `60.0`)_
+    pub apex_threshold: f32,
+    /// Gravity multiplier at the apex of a jump _(default: `1.0`)_
+    pub apex_gravity_multiplier: f32,
+    /// Movement speed multiplier at the apex of a jump _(default: `1.0`)_
+    pub apex_speed_multiplier: f32,
+    /// Maximum fall speed in units per second _(default: `f32::INFINITY`)_
+    pub max_fall_speed: f32,
+    /// Fall faster by holding down while airborne _(default: `false`)_
+    pub fast_fall: bool,
+    /// Gravity multiplier when fast falling _(default: `2.0`)_
+    pub fast_fall_gravity_multiplier: f32,
+    /// Maximum fall speed in units per second when fast falling, if higher than the normal maximum _(default: `1800.0`)_
+    pub fast_fall_max_speed: f32,
+    /// Glide by holding jump while falling _(default: `false`)_
+    pub glide: bool,
+    /// Maximum fall speed in units per second while gliding _(default: `100.0`)_
+    pub glide_fall_speed: f32,
+    /// Time in seconds to accelerate, slow down or turn around while gliding _(default: `0.4`)_
+    pub glide_acceleration_time: f32,
+    /// Maximum time in seconds to glide for before landing again, or `0.0` for no limit _(default: `0.0`)_
+    pub glide_duration: f32,
+    /// Ground pound by pressing down while airborne _(default: `false`)_
+    pub ground_pound: bool,
+    /// Time in seconds to freeze in the air before slamming down _(default: `0.15`)_
+    pub ground_pound_freeze_time: f32,
+    /// Speed in units per second to slam down at _(default: `1500.0`)_
+    pub ground_pound_speed: f32,
     /// Time in seconds after leaving a platform to allow for a jump _(default: `0.08`)_
     pub coyote_time: f32,
+    /// Number of extra jumps allowed while airborne _(default: `0`)_
+    pub air_jumps: u32,
+    /// Allow jumping off walls while airborne _(default: `false`)_
+    pub wall_jump: bool,
+    /// Horizontal push away from the wall when wall jumping, as a multiple of move speed _(default: `1.5`)_
+    pub wall_jump_push: f32,
+    /// Allow dashing with left shift _(default: `false`)_
+    pub dash: bool,
+    /// Distance travelled by a dash _(default: `150.0`)_
+    pub dash_distance: f32,
+    /// Time in seconds a dash lasts _(default: `0.15`)_
+    pub dash_duration: f32,
+    /// Time in seconds after a dash before dashing again _(default: `0.4`)_
+    pub dash_cooldown: f32,
+    /// Number of dashes allowed while airborne, reset on landing _(default: `1`)_
+    pub air_dashes: u32,
+    /// Dash in the held input direction, including diagonals, instead of the facing direction _(default: `false`)_
+    pub dash_eight_way: bool,
+    /// Allow crouching by holding down while grounded _(default: `false`)_
+    pub crouch: bool,
+    /// Collider height while crouching, as a fraction of the standing height _(default: `0.5`)_
+    pub crouch_height: f32,
+    /// Movement speed multiplier while crouching _(default: `0.5`)_
+    pub crouch_speed_multiplier: f32,
+    /// Grab onto ledges when falling past them while holding towards the wall _(default: `false`)_
+    pub ledge_grab: bool,
+    /// Distance past the wall to look for the top of a ledge _(default: `4.0`)_
+    pub ledge_grab_reach: f32,
+    /// Time in seconds to climb up onto a ledge _(default: `0.2`)_
+    pub ledge_climb_time: f32,
+    /// Climbing speed in units per second _(default: `150.0`)_
+    pub climb_speed: f32,
+    /// Gravity multiplier while swimming _(default: `0.2`)_
+    pub swim_gravity_multiplier: f32,
+    /// How quickly vertical speed is slowed while swimming, per second _(default: `3.0`)_
+    pub swim_drag: f32,
+    /// Movement speed multiplier while swimming _(default: `0.6`)_
+    pub swim_speed_multiplier: f32,
+    /// Upward speed of a swim stroke when pressing jump under water, in units per second _(default: `250.0`)_
+    pub swim_stroke_speed: f32,
+    /// Maximum sinking speed in units per second while swimming _(default: `150.0`)_
+    pub swim_max_fall_speed: f32,
+    /// Maximum height of obstacles to step up onto while grounded _(default: `0.0`)_
+    pub step_height: f32,
+    /// Maximum distance to nudge around ceiling corners, or over ledge tops while airborne _(default: `0.0`)_
+    pub corner_correction: f32,
+    /// Collide with other characters which also have this enabled, so they can block and stand on each other _(default: `false`)_
+    pub collide_with_controllers: bool,
+    /// How collisions are detected _(default: `CollisionMode::Rays`)_
+    #[reflect(ignore)]
+    pub collision_mode: CollisionMode,
+    /// Movement per frame above which swept collisions are split into sub-steps _(default: `16.0`)_
+    pub max_step_distance: f32,
+    /// Flip `Sprite` or `TextureAtlasSprite` to match the facing direction _(default: `false`)_
+    pub flip_sprite: bool,
     /// Ray casting inset _(default: `1.0`)_
     pub skin_width: f32,
     /// Horizontal ray count _(default: `6`)_
@@ -53,9 +149,56 @@ impl Default for CharacterController {
             time_to_jump_apex: 0.4,
             acceleration_time_grounded: 0.1,
             acceleration_time_airborne: 0.2,
+            deceleration_time_grounded: 0.1,
+            deceleration_time_airborne: 0.2,
+            turnaround_time_grounded: 0.1,
+            turnaround_time_airborne: 0.2,
+            acceleration_curve: AccelerationCurve::SmoothDamp,
+            gravity_direction: -Vec2::Y,
             gravity_up_multiplier: 1.0,
             gravity_down_multiplier: 1.5,
+            apex_threshold: 60.0,
+            apex_gravity_multiplier: 1.0,
+            apex_speed_multiplier: 1.0,
+            max_fall_speed: f32::INFINITY,
+            fast_fall: false,
+            fast_fall_gravity_multiplier: 2.0,
+            fast_fall_max_speed: 1800.0,
+            glide: false,
+            glide_fall_speed: 100.0,
+            glide_acceleration_time: 0.4,
+            glide_duration: 0.0,
+            ground_pound: false,
+            ground_pound_freeze_time: 0.15,
+            ground_pound_speed: 1500.0,
             coyote_time: 0.08,
+            air_jumps: 0,
+            wall_jump: false,
+            wall_jump_push: 1.5,
+            dash: false,
+            dash_distance: 150.0,
+            dash_duration: 0.15,
+            dash_cooldown: 0.4,
+            air_dashes: 1,
+            dash_eight_way: false,
+            crouch: false,
+            crouch_height: 0.5,
+            crouch_speed_multiplier: 0.5,
+            ledge_grab: false,
+            ledge_grab_reach: 4.0,
+            ledge_climb_time: 0.2,
+            climb_speed: 150.0,
+            swim_gravity_multiplier: 0.2,
+            swim_drag: 3.0,
+            swim_speed_multiplier: 0.6,
+            swim_stroke_speed: 250.0,
+            swim_max_fall_speed: 150.0,
+            step_height: 0.0,
+            corner_correction: 0.0,
+            collide_with_controllers: false,
+            collision_mode: CollisionMode::Rays,
+            max_step_distance: 16.0,
+            flip_sprite: false,
             skin_width: 1.0,
             horizontal_ray_count: 6,
             vertical_ray_count: 4,
@@ -63,8 +206,41 @@ impl Default for CharacterController {
     }
 }
 
-fn move_player(mut query: Query<(&PlayerVelocity, &mut Transform)>) {
-    for (velocity, mut transform) in query.iter_mut() {
-        transform.translation += velocity.0.extend(0.0);
+impl CharacterController {
+    /// Downwards acceleration derived from the jump height and time to jump apex.
+    pub fn gravity(&self) -> f32 {
+        (2.0 * self.jump_height) / self.time_to_jump_apex.powi(2)
+    }
+
+    /// Upwards velocity needed to reach `height`, in the same units as the jump height.
+    pub fn launch_velocity(&self, height: f32) -> f32 {
+        (2.0 * self.gravity() * self.gravity_up_multiplier * height).sqrt()
+    }
+}
+
+/// Easing used to change horizontal speed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelerationCurve {
+    /// Smoothly damped, like a spring.
+    SmoothDamp,
+    /// Follows `t^exponent` over the acceleration time.
+    ///
+    /// An exponent of `1.0` is linear, above `1.0` starts slowly, and below `1.0` starts quickly.
+    Power(f32),
+}
+
+/// How a character detects collisions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionMode {
+    /// Cast a fan of rays from each side, which is fast but can miss colliders thinner than the
+    /// gap between rays.
+    Rays,
+    /// Also sweep the whole shape, so fast movement never passes through or between colliders.
+    Swept,
+}
+
+fn move_player(mut query: Query<(&PlayerVelocity, &Gravity, &mut Transform)>) {
+    for (velocity, gravity, mut transform) in query.iter_mut() {
+        transform.translation += gravity.to_world(velocity.0).extend(0.0);
     }
 }
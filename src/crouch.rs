@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use impacted::CollisionShape;
+
+use crate::{
+    climb::{Climb, Climbable},
+    collisions::{world_bounds, CollisionInfo, SolidFilter},
+    gravity::Gravity,
+    impulse::InputLockout,
+    ray_cast::{Oriented, Ray, RayCast},
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct CrouchPlugin;
+
+impl Plugin for CrouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Crouch>()
+            .add_system(crouch.before(ControllerLabel::Calculate));
+    }
+}
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Crouch {
+    crouching: bool,
+    standing_size: Vec2,
+    /// Collider to restore when standing back up.
+    #[reflect(ignore)]
+    standing_shape: Option<CollisionShape>,
+}
+
+impl Crouch {
+    pub fn is_crouching(&self) -> bool {
+        self.crouching
+    }
+}
+
+fn crouch(
+    input: Res<Input<KeyCode>>,
+    mut controllers: Query<
+        (
+            &CharacterController,
+            &mut Crouch,
+            &mut CollisionShape,
+            &mut Transform,
+            &CollisionInfo,
+            &InputLockout,
+            &Climb,
+            &Gravity,
+        ),
+        With<CharacterController>,
+    >,
+    colliders: Query<(Entity, &CollisionShape, &Transform), SolidFilter>,
+    climbables: Query<(), With<Climbable>>,
+) {
+    for (
+        controller,
+        mut crouch,
+        mut collider,
+        mut transform,
+        collisions,
+        input_lockout,
+        climb,
+        gravity,
+    ) in controllers.iter_mut()
+    {
+        // Holding down on top of a ladder climbs down it instead
+        let on_climbable = collisions
+            .ground
+            .map_or(false, |ground| climbables.get(ground).is_ok());
+        let wants_crouch = controller.crouch
+            && collisions.below
+            && !on_climbable
+            && !climb.is_climbing()
+            && !input_lockout.is_locked()
+            && input.pressed(KeyCode::Down);
+        if wants_crouch == crouch.crouching {
+            continue;
+        }
+
+        // Sizes are relative to gravity, so crouching always shrinks towards the ground
+        let (min, max) = gravity.local_bounds(world_bounds(&collider, &transform));
+        let size = max - min;
+
+        if wants_crouch {
+            let crouch_height = size.y * controller.crouch_height;
+            crouch.standing_size = size;
+            crouch.standing_shape = Some(collider.clone());
+            crouch.crouching = true;
+            let crouch_size = gravity.to_world(Vec2::new(size.x, crouch_height)).abs();
+            *collider = CollisionShape::new_rectangle(crouch_size.x, crouch_size.y);
+            // Keep the feet anchored to the ground
+            let offset = gravity.up() * (size.y - crouch_height) / 2.0;
+            transform.translation -= offset.extend(0.0);
+        } else {
+            // Only stand up if there is room overhead
+            let height_difference = crouch.standing_size.y - size.y;
+            let ray_length = height_difference + controller.skin_width;
+            let ray_spacing =
+                (size.x - controller.skin_width * 2.0) / (controller.vertical_ray_count - 1) as f32;
+            let colliders = Oriented::new(&colliders, gravity);
+            let blocked = (0..controller.vertical_ray_count).any(|i| {
+                let ray_origin = Vec2::new(
+                    min.x + controller.skin_width + ray_spacing * i as f32,
+                    max.y - controller.skin_width,
+                );
+                colliders
+                    .ray_cast(Ray::new(ray_origin, Vec2::Y).with_length(ray_length))
+                    .is_some()
+            });
+            if blocked {
+                continue;
+            }
+
+            crouch.crouching = false;
+            if let Some(standing_shape) = crouch.standing_shape.take() {
+                *collider = standing_shape;
+            }
+            let offset = gravity.up() * height_difference / 2.0;
+            transform.translation += offset.extend(0.0);
+        }
+    }
+}
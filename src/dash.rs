@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{CollisionEvent, CollisionInfo, CollisionSide, PlayerVelocity},
+    ground_pound::GroundPounding,
+    impulse::InputLockout,
+    input::ControllerInput,
+    ledge::LedgeGrab,
+    state::Facing,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Dash>()
+            .add_event::<DashStarted>()
+            .add_event::<DashEnded>()
+            .add_system(
+                dash.label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::Dash)
+                    .after(ControllerLabel::Input),
+            )
+            .add_system(end_dash_on_collision.after(ControllerLabel::Collisions))
+            .add_system(reset_air_dashes.after(ControllerLabel::Collisions));
+    }
+}
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Dash {
+    direction: Vec2,
+    time_left: f32,
+    cooldown: f32,
+    /// Number of dashes used since last touching the ground.
+    pub air_dash_count: u32,
+}
+
+impl Dash {
+    pub fn is_dashing(&self) -> bool {
+        self.time_left > 0.0
+    }
+
+    /// Direction of the current dash, or zero when not dashing.
+    pub fn direction(&self) -> Vec2 {
+        if self.is_dashing() {
+            self.direction
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    /// Ends the current dash early.
+    pub(crate) fn cancel(&mut self) {
+        self.time_left = 0.0;
+    }
+}
+
+/// Sent when a character starts dashing.
+#[derive(Clone, Copy, Debug)]
+pub struct DashStarted {
+    pub entity: Entity,
+    pub direction: Vec2,
+}
+
+/// Sent when a character stops dashing.
+#[derive(Clone, Copy, Debug)]
+pub struct DashEnded {
+    pub entity: Entity,
+    /// Whether the dash was cut short by running into something.
+    pub interrupted: bool,
+}
+
+fn dash(
+    time: Res<Time>,
+    input: Res<Input<KeyCode>>,
+    mut query: Query<(
+        Entity,
+        &CharacterController,
+        &mut Dash,
+        &mut PlayerVelocity,
+        &CollisionInfo,
+        &ControllerInput,
+        &Facing,
+        &InputLockout,
+        &GroundPounding,
+        &LedgeGrab,
+    )>,
+    mut dash_ended_events: EventWriter<DashEnded>,
+    mut dash_started_events: EventWriter<DashStarted>,
+) {
+    for (
+        entity,
+        controller,
+        mut dash,
+        mut velocity,
+        collisions,
+        controller_input,
+        facing,
+        input_lockout,
+        ground_pound,
+        ledge,
+    ) in query.iter_mut()
+    {
+        let dash_speed = controller.dash_distance / controller.dash_duration.max(0.0001);
+        dash.cooldown = (dash.cooldown - time.delta_seconds()).max(0.0);
+
+        if dash.is_dashing() {
+            dash.time_left -= time.delta_seconds();
+            if dash.is_dashing() {
+                // Gravity is suspended by overriding the velocity for the whole dash
+                velocity.0 = dash.direction * dash_speed * time.delta_seconds();
+            } else {
+                // Leave the dash at regular movement speed
+                velocity.0 = dash.direction * controller.move_speed * time.delta_seconds();
+                velocity.0.y = velocity.0.y.min(0.0);
+                dash_ended_events.send(DashEnded {
+                    entity,
+                    interrupted: false,
+                });
+            }
+            continue;
+        }
+
+        if !controller.dash
+            || dash.cooldown > 0.0
+            || input_lockout.is_locked()
+            || ground_pound.is_ground_pounding()
+            || ledge.is_hanging()
+            || !input.just_pressed(KeyCode::LShift)
+        {
+            continue;
+        }
+
+        if !collisions.below {
+            if dash.air_dash_count >= controller.air_dashes {
+                continue;
+            }
+            dash.air_dash_count += 1;
+        }
+
+        let direction = if controller.dash_eight_way && controller_input.0 != Vec2::ZERO {
+            controller_input.0.normalize()
+        } else {
+            Vec2::X * facing.direction()
+        };
+
+        dash.direction = direction;
+        dash.time_left = controller.dash_duration;
+        dash.cooldown = controller.dash_duration + controller.dash_cooldown;
+        velocity.0 = direction * dash_speed * time.delta_seconds();
+        dash_started_events.send(DashStarted { entity, direction });
+    }
+}
+
+fn end_dash_on_collision(
+    mut query: Query<(Entity, &mut Dash, &CollisionInfo)>,
+    mut dash_ended_events: EventWriter<DashEnded>,
+) {
+    for (entity, mut dash, collisions) in query.iter_mut() {
+        if !dash.is_dashing() {
+            continue;
+        }
+
+        let direction = dash.direction;
+        let blocked = (direction.x < 0.0 && collisions.left)
+            || (direction.x > 0.0 && collisions.right)
+            || (direction.y < 0.0 && collisions.below)
+            || (direction.y > 0.0 && collisions.above);
+
+        if blocked {
+            dash.cancel();
+            dash_ended_events.send(DashEnded {
+                entity,
+                interrupted: true,
+            });
+        }
+    }
+}
+
+fn reset_air_dashes(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query: Query<&mut Dash>,
+) {
+    for CollisionEvent { entity, side } in collision_events.iter() {
+        if let CollisionSide::Below(true) = side {
+            if let Ok(mut dash) = query.get_mut(*entity) {
+                dash.air_dash_count = 0;
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use bevy::{core::Stopwatch, prelude::*};
+
+use crate::{
+    collisions::{CollisionEvent, CollisionSide, JumpCount, PlayerVelocity},
+    ControllerLabel,
+};
+
+pub(crate) struct EventsPlugin;
+
+impl Plugin for EventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Airtime>()
+            .register_type::<FallSpeed>()
+            .add_event::<Jumped>()
+            .add_event::<Landed>()
+            .add_event::<LeftGround>()
+            .add_event::<HitCeiling>()
+            .add_event::<TouchedWall>()
+            .add_system(
+                record_fall_speed
+                    .after(ControllerLabel::Calculate)
+                    .before(ControllerLabel::Collisions),
+            )
+            .add_system(
+                send_gameplay_events
+                    .label(ControllerLabel::Events)
+                    .after(ControllerLabel::Collisions),
+            );
+    }
+}
+
+/// Time spent airborne since last leaving the ground.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Airtime(pub(crate) Stopwatch);
+
+/// Downward speed before collisions were resolved this frame.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct FallSpeed(pub(crate) f32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JumpKind {
+    /// Jumped while standing on the ground.
+    Ground,
+    /// Jumped shortly after walking off a platform.
+    Coyote,
+    /// Jumped mid-air using one of the controller's air jumps.
+    Air,
+    /// Jumped off a wall.
+    Wall,
+    /// Jumped off a ladder or other climbable.
+    Climb,
+    /// Swam upwards while under water.
+    SwimStroke,
+    /// Jumped out of water from the surface.
+    OutOfWater,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WallSide {
+    Left,
+    Right,
+}
+
+/// Sent when a character jumps.
+#[derive(Clone, Copy, Debug)]
+pub struct Jumped {
+    pub entity: Entity,
+    pub kind: JumpKind,
+}
+
+/// Sent when a character lands on the ground.
+#[derive(Clone, Copy, Debug)]
+pub struct Landed {
+    pub entity: Entity,
+    /// Downward speed at the moment of landing, in the same units as [`PlayerVelocity`].
+    pub fall_speed: f32,
+    /// Time in seconds spent in the air.
+    pub airtime: f32,
+}
+
+/// Sent when a character leaves the ground.
+#[derive(Clone, Copy, Debug)]
+pub struct LeftGround {
+    pub entity: Entity,
+    /// Whether the character left the ground by jumping, rather than walking off a platform.
+    pub by_jump: bool,
+}
+
+/// Sent when a character bumps its head on a ceiling.
+#[derive(Clone, Copy, Debug)]
+pub struct HitCeiling {
+    pub entity: Entity,
+}
+
+/// Sent when a character runs into a wall.
+#[derive(Clone, Copy, Debug)]
+pub struct TouchedWall {
+    pub entity: Entity,
+    pub side: WallSide,
+}
+
+fn record_fall_speed(mut query: Query<(&PlayerVelocity, &mut FallSpeed)>) {
+    for (velocity, mut fall_speed) in query.iter_mut() {
+        fall_speed.0 = (-velocity.0.y).max(0.0);
+    }
+}
+
+fn send_gameplay_events(
+    time: Res<Time>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query: Query<(&mut Airtime, &FallSpeed, &JumpCount)>,
+    mut landed_events: EventWriter<Landed>,
+    mut left_ground_events: EventWriter<LeftGround>,
+    mut hit_ceiling_events: EventWriter<HitCeiling>,
+    mut touched_wall_events: EventWriter<TouchedWall>,
+) {
+    for CollisionEvent { entity, side } in collision_events.iter() {
+        let entity = *entity;
+        match side {
+            CollisionSide::Below(true) => {
+                if let Ok((mut airtime, fall_speed, _)) = query.get_mut(entity) {
+                    landed_events.send(Landed {
+                        entity,
+                        fall_speed: fall_speed.0,
+                        airtime: airtime.0.elapsed_secs(),
+                    });
+                    airtime.0.pause();
+                    airtime.0.reset();
+                }
+            }
+            CollisionSide::Below(false) => {
+                if let Ok((mut airtime, _, jump_count)) = query.get_mut(entity) {
+                    left_ground_events.send(LeftGround {
+                        entity,
+                        by_jump: jump_count.0 > 0,
+                    });
+                    airtime.0.reset();
+                    airtime.0.unpause();
+                }
+            }
+            CollisionSide::Above(true) => hit_ceiling_events.send(HitCeiling { entity }),
+            CollisionSide::Left(true) => touched_wall_events.send(TouchedWall {
+                entity,
+                side: WallSide::Left,
+            }),
+            CollisionSide::Right(true) => touched_wall_events.send(TouchedWall {
+                entity,
+                side: WallSide::Right,
+            }),
+            _ => {}
+        }
+    }
+
+    for (mut airtime, _, _) in query.iter_mut() {
+        airtime.0.tick(time.delta());
+    }
+}
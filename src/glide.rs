@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use crate::{
+    climb::Climb,
+    collisions::{CollisionInfo, PlayerVelocity},
+    dash::Dash,
+    ground_pound::GroundPounding,
+    impulse::InputLockout,
+    ledge::LedgeGrab,
+    water::Swim,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct GlidePlugin;
+
+impl Plugin for GlidePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Glide>()
+            .add_event::<GlideStarted>()
+            .add_event::<GlideEnded>()
+            .add_system(
+                glide
+                    .label(ControllerLabel::Calculate)
+                    .before(ControllerLabel::Input),
+            );
+    }
+}
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Glide {
+    gliding: bool,
+    time_used: f32,
+}
+
+impl Glide {
+    pub fn is_gliding(&self) -> bool {
+        self.gliding
+    }
+
+    /// Time in seconds spent gliding since last touching the ground.
+    pub fn time_used(&self) -> f32 {
+        self.time_used
+    }
+
+    /// Stops gliding, such as when a ground pound starts.
+    pub(crate) fn cancel(&mut self) {
+        self.gliding = false;
+    }
+}
+
+/// Sent when a character starts gliding.
+#[derive(Clone, Copy, Debug)]
+pub struct GlideStarted {
+    pub entity: Entity,
+}
+
+/// Sent when a character stops gliding.
+#[derive(Clone, Copy, Debug)]
+pub struct GlideEnded {
+    pub entity: Entity,
+    /// Whether the glide ended by running out of glide time.
+    pub exhausted: bool,
+}
+
+fn glide(
+    time: Res<Time>,
+    input: Res<Input<KeyCode>>,
+    mut query: Query<(
+        Entity,
+        &CharacterController,
+        &mut Glide,
+        &PlayerVelocity,
+        &CollisionInfo,
+        &InputLockout,
+        &Dash,
+        &GroundPounding,
+        &LedgeGrab,
+        &Climb,
+        &Swim,
+    )>,
+    mut glide_started_events: EventWriter<GlideStarted>,
+    mut glide_ended_events: EventWriter<GlideEnded>,
+) {
+    for (
+        entity,
+        controller,
+        mut glide,
+        velocity,
+        collisions,
+        input_lockout,
+        dash,
+        ground_pound,
+        ledge,
+        climb,
+        swim,
+    ) in query.iter_mut()
+    {
+        if collisions.below {
+            glide.time_used = 0.0;
+        }
+
+        let holding_jump = !input_lockout.is_locked()
+            && (input.pressed(KeyCode::Space) || input.pressed(KeyCode::Up));
+        let exhausted =
+            controller.glide_duration > 0.0 && glide.time_used >= controller.glide_duration;
+        let gliding = controller.glide
+            && holding_jump
+            && !exhausted
+            && !collisions.below
+            && velocity.0.y < 0.0
+            && !dash.is_dashing()
+            && !ground_pound.is_ground_pounding()
+            && !ledge.is_hanging()
+            && !climb.is_climbing()
+            && !swim.is_swimming();
+
+        if gliding {
+            glide.time_used += time.delta_seconds();
+        }
+
+        if gliding != glide.gliding {
+            glide.gliding = gliding;
+            if gliding {
+                glide_started_events.send(GlideStarted { entity });
+            } else {
+                glide_ended_events.send(GlideEnded { entity, exhausted });
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+use impacted::CollisionShape;
+
+use crate::{
+    collisions::{bounds_overlap, world_bounds, PlayerVelocity},
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct GravityPlugin;
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Gravity>()
+            .register_type::<GravityZone>()
+            .add_event::<GravityChanged>()
+            .add_system(
+                update_gravity
+                    .label(ControllerLabel::Calculate)
+                    .before(ControllerLabel::Input)
+                    // Ray origins must be in the same frame as the velocity
+                    .before(ControllerLabel::RayOrigins),
+            );
+    }
+}
+
+/// Direction of gravity currently acting on a character, from its controller or a
+/// [`GravityZone`].
+///
+/// Movement and collisions are relative to gravity, so [`PlayerVelocity`] and `CollisionInfo`
+/// are in a frame where `-y` points along gravity. Gravity is always snapped to the nearest axis.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Gravity {
+    direction: Vec2,
+}
+
+impl Gravity {
+    pub fn direction(&self) -> Vec2 {
+        self.direction
+    }
+
+    /// World direction pointing away from the ground.
+    pub fn up(&self) -> Vec2 {
+        -self.direction
+    }
+
+    /// World direction of positive `x` movement, kept pointing right unless gravity is sideways.
+    pub fn right(&self) -> Vec2 {
+        let up = self.up();
+        if up.y < 0.0 {
+            Vec2::new(-up.y, up.x)
+        } else {
+            Vec2::new(up.y, -up.x)
+        }
+    }
+
+    /// Converts a vector relative to gravity into world space.
+    pub fn to_world(&self, local: Vec2) -> Vec2 {
+        self.right() * local.x + self.up() * local.y
+    }
+
+    /// Converts a world space vector to be relative to gravity.
+    pub fn to_local(&self, world: Vec2) -> Vec2 {
+        Vec2::new(world.dot(self.right()), world.dot(self.up()))
+    }
+
+    /// Converts a world space bounding box, as `(min, max)`, to be relative to gravity.
+    pub(crate) fn local_bounds(&self, (min, max): (Vec2, Vec2)) -> (Vec2, Vec2) {
+        let a = self.to_local(min);
+        let b = self.to_local(max);
+        (a.min(b), a.max(b))
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity {
+            direction: -Vec2::Y,
+        }
+    }
+}
+
+/// Overrides the gravity direction of characters inside a collider, such as an upside-down
+/// section of a level.
+///
+/// Gravity zones don't block movement.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct GravityZone {
+    pub direction: Vec2,
+}
+
+impl Default for GravityZone {
+    fn default() -> Self {
+        GravityZone {
+            direction: -Vec2::Y,
+        }
+    }
+}
+
+/// Sent when the gravity acting on a character changes direction.
+#[derive(Clone, Copy, Debug)]
+pub struct GravityChanged {
+    pub entity: Entity,
+    pub direction: Vec2,
+}
+
+/// Snaps a direction to the nearest axis, falling back to down.
+fn snap_to_axis(direction: Vec2) -> Vec2 {
+    if direction.x.abs() > direction.y.abs() {
+        Vec2::X * direction.x.signum()
+    } else if direction.y != 0.0 {
+        Vec2::Y * direction.y.signum()
+    } else {
+        -Vec2::Y
+    }
+}
+
+fn update_gravity(
+    mut controllers: Query<
+        (
+            Entity,
+            &CharacterController,
+            &mut Gravity,
+            &mut PlayerVelocity,
+            &CollisionShape,
+            &Transform,
+        ),
+        With<CharacterController>,
+    >,
+    zones: Query<(&GravityZone, &CollisionShape, &Transform), Without<CharacterController>>,
+    mut gravity_changed_events: EventWriter<GravityChanged>,
+) {
+    for (entity, controller, mut gravity, mut velocity, collider, transform) in
+        controllers.iter_mut()
+    {
+        let bounds = world_bounds(collider, transform);
+        let direction = zones
+            .iter()
+            .find(|(_, zone_collider, zone_transform)| {
+                bounds_overlap(bounds, world_bounds(zone_collider, zone_transform))
+            })
+            .map(|(zone, _, _)| zone.direction)
+            .unwrap_or(controller.gravity_direction);
+        let direction = snap_to_axis(direction);
+
+        if gravity.direction == direction {
+            continue;
+        }
+
+        // Keep moving the same way in world space
+        let world_velocity = gravity.to_world(velocity.0);
+        gravity.direction = direction;
+        velocity.0 = gravity.to_local(world_velocity);
+
+        gravity_changed_events.send(GravityChanged { entity, direction });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_to_axis_picks_nearest_axis() {
+        assert_eq!(snap_to_axis(Vec2::new(0.8, -0.6)), Vec2::X);
+        assert_eq!(snap_to_axis(Vec2::new(-0.1, 2.0)), Vec2::Y);
+        assert_eq!(snap_to_axis(Vec2::ZERO), -Vec2::Y);
+    }
+
+    #[test]
+    fn local_space_round_trips_through_world_space() {
+        for direction in [-Vec2::Y, Vec2::Y, Vec2::X, -Vec2::X] {
+            let gravity = Gravity { direction };
+            let local = Vec2::new(3.0, -2.0);
+
+            assert_eq!(gravity.to_local(gravity.to_world(local)), local);
+            assert_eq!(gravity.to_world(-Vec2::Y), direction);
+        }
+    }
+
+    #[test]
+    fn right_stays_right_unless_gravity_is_sideways() {
+        assert_eq!(Gravity { direction: Vec2::Y }.right(), Vec2::X);
+        assert_eq!(Gravity { direction: Vec2::X }.right(), Vec2::Y);
+    }
+
+    #[test]
+    fn local_bounds_keeps_min_below_max() {
+        let gravity = Gravity { direction: Vec2::X };
+        assert_eq!(
+            gravity.local_bounds((Vec2::ZERO, Vec2::new(10.0, 20.0))),
+            (Vec2::new(0.0, -10.0), Vec2::new(20.0, 0.0))
+        );
+    }
+}
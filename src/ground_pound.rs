@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+use crate::{
+    climb::Climb,
+    collisions::{CollisionInfo, PlayerVelocity},
+    dash::Dash,
+    glide::{Glide, GlideEnded},
+    impulse::InputLockout,
+    ledge::LedgeGrab,
+    water::Swim,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct GroundPoundPlugin;
+
+impl Plugin for GroundPoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GroundPounding>()
+            .add_event::<GroundPoundStarted>()
+            .add_event::<GroundPound>()
+            .add_system(
+                ground_pound
+                    .label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::GroundPound)
+                    .after(ControllerLabel::Dash),
+            )
+            .add_system(
+                land_ground_pound
+                    .label(ControllerLabel::Events)
+                    .after(ControllerLabel::Collisions),
+            );
+    }
+}
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct GroundPounding {
+    active: bool,
+    freeze_time_left: f32,
+}
+
+impl GroundPounding {
+    /// Whether the character is frozen in the air or slamming downwards.
+    pub fn is_ground_pounding(&self) -> bool {
+        self.active
+    }
+
+    /// Whether the character is frozen in the air before slamming downwards.
+    pub fn is_frozen(&self) -> bool {
+        self.active && self.freeze_time_left > 0.0
+    }
+
+    /// Stops the ground pound without landing.
+    pub(crate) fn cancel(&mut self) {
+        self.active = false;
+    }
+}
+
+/// Sent when a character starts a ground pound in the air.
+#[derive(Clone, Copy, Debug)]
+pub struct GroundPoundStarted {
+    pub entity: Entity,
+}
+
+/// Sent when a ground pounding character hits the ground.
+#[derive(Clone, Copy, Debug)]
+pub struct GroundPound {
+    pub entity: Entity,
+    /// Collider that was slammed into, such as a block to break.
+    pub collider: Option<Entity>,
+}
+
+fn ground_pound(
+    time: Res<Time>,
+    input: Res<Input<KeyCode>>,
+    mut query: Query<(
+        Entity,
+        &CharacterController,
+        &mut GroundPounding,
+        &mut PlayerVelocity,
+        &CollisionInfo,
+        &InputLockout,
+        &Dash,
+        &mut Glide,
+        &LedgeGrab,
+        &Climb,
+        &Swim,
+    )>,
+    mut ground_pound_started_events: EventWriter<GroundPoundStarted>,
+    mut glide_ended_events: EventWriter<GlideEnded>,
+) {
+    for (
+        entity,
+        controller,
+        mut ground_pound,
+        mut velocity,
+        collisions,
+        input_lockout,
+        dash,
+        mut glide,
+        ledge,
+        climb,
+        swim,
+    ) in query.iter_mut()
+    {
+        if ground_pound.active && (climb.is_climbing() || swim.is_swimming()) {
+            ground_pound.cancel();
+        }
+
+        if ground_pound.active {
+            if ground_pound.is_frozen() {
+                // Hang in the air briefly before slamming down
+                ground_pound.freeze_time_left -= time.delta_seconds();
+                velocity.0 = Vec2::ZERO;
+            } else {
+                velocity.0 = -Vec2::Y * controller.ground_pound_speed * time.delta_seconds();
+            }
+            continue;
+        }
+
+        if !controller.ground_pound
+            || collisions.below
+            || input_lockout.is_locked()
+            || dash.is_dashing()
+            || ledge.is_hanging()
+            || climb.is_climbing()
+            || swim.is_swimming()
+            || !input.just_pressed(KeyCode::Down)
+        {
+            continue;
+        }
+
+        ground_pound.active = true;
+        ground_pound.freeze_time_left = controller.ground_pound_freeze_time;
+        velocity.0 = Vec2::ZERO;
+        ground_pound_started_events.send(GroundPoundStarted { entity });
+
+        // Slamming down out of a glide ends it
+        if glide.is_gliding() {
+            glide.cancel();
+            glide_ended_events.send(GlideEnded {
+                entity,
+                exhausted: false,
+            });
+        }
+    }
+}
+
+fn land_ground_pound(
+    mut query: Query<(Entity, &mut GroundPounding, &CollisionInfo)>,
+    mut ground_pound_events: EventWriter<GroundPound>,
+) {
+    for (entity, mut ground_pound, collisions) in query.iter_mut() {
+        if ground_pound.active && !ground_pound.is_frozen() && collisions.below {
+            ground_pound.active = false;
+            ground_pound_events.send(GroundPound {
+                entity,
+                collider: collisions.ground,
+            });
+        }
+    }
+}
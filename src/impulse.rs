@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use crate::{
+    climb::Climb,
+    collisions::PlayerVelocity,
+    dash::{Dash, DashEnded},
+    ground_pound::GroundPounding,
+    input::VelocityXSmoothing,
+    ledge::{LedgeGrab, LedgeReleased},
+    ControllerLabel,
+};
+
+pub(crate) struct ImpulsePlugin;
+
+impl Plugin for ImpulsePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<InputLockout>()
+            .add_event::<ControllerImpulse>()
+            .add_system(
+                apply_impulses
+                    .label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::Impulse)
+                    .after(ControllerLabel::LedgeGrab),
+            );
+    }
+}
+
+/// Time in seconds remaining before the character responds to input again.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct InputLockout(pub f32);
+
+impl InputLockout {
+    pub fn is_locked(&self) -> bool {
+        self.0 > 0.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImpulseMode {
+    /// Add the impulse to the current velocity.
+    Add,
+    /// Replace the current velocity with the impulse.
+    Set,
+}
+
+/// Send to push a character, such as knockback from an enemy hit, a spring or an explosion.
+///
+/// The impulse is in the same units as [`PlayerVelocity`]. It interrupts any dash, ground pound,
+/// ledge hang or climb, so it is never overridden by them.
+#[derive(Clone, Copy, Debug)]
+pub struct ControllerImpulse {
+    pub entity: Entity,
+    pub impulse: Vec2,
+    pub mode: ImpulseMode,
+    /// Time in seconds to ignore input for, so the impulse can't be cancelled straight away.
+    pub input_lockout: f32,
+}
+
+impl ControllerImpulse {
+    pub fn new(entity: Entity, impulse: Vec2) -> Self {
+        ControllerImpulse {
+            entity,
+            impulse,
+            mode: ImpulseMode::Add,
+            input_lockout: 0.0,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: ImpulseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_input_lockout(mut self, input_lockout: f32) -> Self {
+        self.input_lockout = input_lockout;
+        self
+    }
+}
+
+fn apply_impulses(
+    mut impulse_events: EventReader<ControllerImpulse>,
+    mut query: Query<(
+        &mut PlayerVelocity,
+        &mut VelocityXSmoothing,
+        &mut InputLockout,
+        &mut Dash,
+        &mut GroundPounding,
+        &mut LedgeGrab,
+        &mut Climb,
+    )>,
+    mut dash_ended_events: EventWriter<DashEnded>,
+    mut ledge_released_events: EventWriter<LedgeReleased>,
+) {
+    for impulse in impulse_events.iter() {
+        if let Ok((
+            mut velocity,
+            mut vel_x_smoothing,
+            mut input_lockout,
+            mut dash,
+            mut ground_pound,
+            mut ledge,
+            mut climb,
+        )) = query.get_mut(impulse.entity)
+        {
+            if dash.is_dashing() {
+                dash.cancel();
+                dash_ended_events.send(DashEnded {
+                    entity: impulse.entity,
+                    interrupted: true,
+                });
+            }
+            if ledge.is_hanging() {
+                ledge.release();
+                ledge_released_events.send(LedgeReleased {
+                    entity: impulse.entity,
+                });
+            }
+            ground_pound.cancel();
+            climb.climbing = false;
+
+            match impulse.mode {
+                ImpulseMode::Add => velocity.0 += impulse.impulse,
+                ImpulseMode::Set => velocity.0 = impulse.impulse,
+            }
+            // Start smoothing afresh from the new velocity
+            vel_x_smoothing.0 = 0.0;
+            input_lockout.0 = input_lockout.0.max(impulse.input_lockout);
+        }
+    }
+}
@@ -1,8 +1,21 @@
 use bevy::prelude::*;
 
 use crate::{
+    climb::Climb,
     collisions::{CollisionInfo, CoyoteStopwatch, JumpCount, PlayerVelocity},
-    smooth_damp, CharacterController, ControllerLabel,
+    controller::AccelerationCurve,
+    crouch::Crouch,
+    dash::Dash,
+    ease_towards,
+    events::{JumpKind, Jumped},
+    glide::Glide,
+    ground_pound::GroundPounding,
+    impulse::InputLockout,
+    ledge::LedgeGrab,
+    smooth_damp,
+    surface::SurfaceMaterial,
+    water::{Swim, Water},
+    CharacterController, ControllerLabel,
 };
 
 pub(crate) struct InputPlugin;
@@ -10,43 +23,80 @@ pub(crate) struct InputPlugin;
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<VelocityXSmoothing>()
-            .add_system(player_input.label(ControllerLabel::Calculate));
+            .register_type::<ControllerInput>()
+            .add_system(
+                player_input
+                    .label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::Input),
+            );
     }
 }
 
 #[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
-pub struct VelocityXSmoothing(f32);
+pub struct VelocityXSmoothing(pub(crate) f32);
+
+/// Raw movement input direction for the current frame.
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct ControllerInput(pub Vec2);
 
 fn player_input(
     time: Res<Time>,
     input: Res<Input<KeyCode>>,
+    mut jumped_events: EventWriter<Jumped>,
+    surfaces: Query<&SurfaceMaterial>,
+    waters: Query<&Water>,
     mut query: Query<(
+        Entity,
         &CharacterController,
         &mut PlayerVelocity,
         &mut VelocityXSmoothing,
+        &mut ControllerInput,
         &mut JumpCount,
         &CoyoteStopwatch,
         &CollisionInfo,
+        &mut InputLockout,
+        (
+            &Dash,
+            &Crouch,
+            &LedgeGrab,
+            &mut Climb,
+            &Swim,
+            &Glide,
+            &GroundPounding,
+        ),
     )>,
 ) {
     for (
+        entity,
         controller,
         mut velocity,
         mut vel_x_smoothing,
+        mut controller_input,
         mut jump_count,
         coyote_stopwatch,
         collisions,
+        mut input_lockout,
+        (dash, crouch, ledge, mut climb, swim, glide, ground_pound),
     ) in query.iter_mut()
     {
-        let gravity = -(2.0 * controller.jump_height) / controller.time_to_jump_apex.powi(2);
+        let gravity = -controller.gravity();
         let jump_velocity = gravity.abs() * controller.time_to_jump_apex;
 
-        // Reset Y velocity if touching above or below
-        if collisions.below || collisions.above {
+        // Reset Y velocity if moving into the ground or ceiling
+        if (collisions.below && velocity.0.y < 0.0) || (collisions.above && velocity.0.y > 0.0) {
             velocity.0.y = 0.0;
         }
 
+        let surface = collisions
+            .ground
+            .filter(|_| collisions.below)
+            .and_then(|ground| surfaces.get(ground).ok())
+            .copied()
+            .unwrap_or_default();
+        let water = swim.water().and_then(|water| waters.get(water).ok());
+
         let mut input_raw = Vec2::ZERO;
 
         // Horizontal movement
@@ -56,33 +106,171 @@ fn player_input(
             input_raw.x = 1.0;
         }
 
-        // Jumping
-        if (input.just_pressed(KeyCode::Up) || input.just_pressed(KeyCode::Space))
-            && (collisions.below || coyote_stopwatch.0.elapsed_secs() <= controller.coyote_time)
-            && jump_count.0 == 0
+        // Vertical movement
+        if input.pressed(KeyCode::Down) && !input.pressed(KeyCode::Up) {
+            input_raw.y = -1.0;
+        } else if input.pressed(KeyCode::Up) && !input.pressed(KeyCode::Down) {
+            input_raw.y = 1.0;
+        }
+
+        // Ignore input while locked out
+        let locked_out = input_lockout.is_locked();
+        if locked_out {
+            input_lockout.0 = (input_lockout.0 - time.delta_seconds()).max(0.0);
+            input_raw = Vec2::ZERO;
+        }
+
+        controller_input.0 = input_raw;
+
+        // Jumping, up is used for climbing while on a ladder
+        let jump_pressed = input.just_pressed(KeyCode::Space)
+            || (!climb.is_climbing() && input.just_pressed(KeyCode::Up));
+        if !locked_out
+            && !dash.is_dashing()
+            && !ledge.is_hanging()
+            && !ground_pound.is_ground_pounding()
+            && jump_pressed
         {
-            jump_count.0 += 1;
-            velocity.0.y = jump_velocity;
+            let kind = if climb.is_climbing() {
+                Some(JumpKind::Climb)
+            } else if swim.at_surface() {
+                Some(JumpKind::OutOfWater)
+            } else if swim.is_swimming() {
+                Some(JumpKind::SwimStroke)
+            } else if collisions.below && jump_count.0 == 0 {
+                Some(JumpKind::Ground)
+            } else if coyote_stopwatch.0.elapsed_secs() <= controller.coyote_time
+                && jump_count.0 == 0
+            {
+                Some(JumpKind::Coyote)
+            } else if controller.wall_jump && (collisions.left || collisions.right) {
+                Some(JumpKind::Wall)
+            } else if jump_count.0.max(1) <= controller.air_jumps {
+                // Walking off a platform without jumping uses up the ground jump
+                Some(JumpKind::Air)
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                match kind {
+                    JumpKind::Ground | JumpKind::Coyote => jump_count.0 += 1,
+                    JumpKind::Climb => {
+                        climb.climbing = false;
+                        jump_count.0 = 1;
+                    }
+                    JumpKind::OutOfWater => jump_count.0 = 1,
+                    JumpKind::SwimStroke => {}
+                    JumpKind::Air => jump_count.0 = jump_count.0.max(1) + 1,
+                    JumpKind::Wall => {
+                        // Wall jumps refresh air jumps
+                        jump_count.0 = 1;
+                        let direction_x = if collisions.left { 1.0 } else { -1.0 };
+                        velocity.0.x = direction_x
+                            * controller.move_speed
+                            * controller.wall_jump_push
+                            * time.delta_seconds();
+                    }
+                }
+                velocity.0.y = if kind == JumpKind::SwimStroke {
+                    controller.swim_stroke_speed * time.delta_seconds()
+                } else {
+                    jump_velocity
+                };
+                jumped_events.send(Jumped { entity, kind });
+            }
         }
 
+        // Near the top of a jump
+        let at_apex = !collisions.below
+            && jump_count.0 > 0
+            && velocity.0.y.abs() < controller.apex_threshold * time.delta_seconds();
+
         // Smooth x movement
-        let target_velocity_x = input_raw.x * controller.move_speed * time.delta_seconds();
-        let acceleration_time = if collisions.below {
-            controller.acceleration_time_grounded
+        let mut target_velocity_x = input_raw.x * controller.move_speed * time.delta_seconds();
+        if crouch.is_crouching() {
+            target_velocity_x *= controller.crouch_speed_multiplier;
+        }
+        if at_apex {
+            target_velocity_x *= controller.apex_speed_multiplier;
+        }
+        target_velocity_x *= surface.max_speed_multiplier;
+        if water.is_some() {
+            target_velocity_x *= controller.swim_speed_multiplier;
+        }
+        let turning_around = target_velocity_x * velocity.0.x < 0.0;
+        let slowing_down = !turning_around && target_velocity_x.abs() < velocity.0.x.abs();
+        let acceleration_time = match (turning_around, slowing_down, collisions.below) {
+            (true, _, true) => controller.turnaround_time_grounded,
+            (true, _, false) => controller.turnaround_time_airborne,
+            (false, true, true) => controller.deceleration_time_grounded,
+            (false, true, false) => controller.deceleration_time_airborne,
+            (false, false, true) => controller.acceleration_time_grounded,
+            (false, false, false) => controller.acceleration_time_airborne,
+        };
+        let acceleration_time = if glide.is_gliding() {
+            controller.glide_acceleration_time
+        } else {
+            acceleration_time
+        };
+        let acceleration_time = if turning_around || slowing_down {
+            acceleration_time / surface.friction
         } else {
-            controller.acceleration_time_airborne
+            acceleration_time / surface.acceleration_multiplier
         };
-        velocity.0.x = smooth_damp(
-            velocity.0.x,
-            target_velocity_x,
-            &mut vel_x_smoothing.0,
-            acceleration_time,
-            f32::INFINITY,
-            time.delta_seconds(),
-        );
+        // Let impulses play out while locked out, instead of smoothing them away
+        if !locked_out {
+            velocity.0.x = match controller.acceleration_curve {
+                AccelerationCurve::SmoothDamp => smooth_damp(
+                    velocity.0.x,
+                    target_velocity_x,
+                    &mut vel_x_smoothing.0,
+                    acceleration_time,
+                    f32::INFINITY,
+                    time.delta_seconds(),
+                ),
+                AccelerationCurve::Power(exponent) => {
+                    let mut range = controller.move_speed * time.delta_seconds();
+                    if turning_around {
+                        range *= 2.0;
+                    }
+                    ease_towards(
+                        velocity.0.x,
+                        target_velocity_x,
+                        range,
+                        acceleration_time,
+                        exponent,
+                        time.delta_seconds(),
+                    )
+                }
+            };
+        }
+
+        // Climbing replaces gravity with moving up and down at a constant speed
+        if climb.is_climbing() {
+            velocity.0.y = input_raw.y * controller.climb_speed * time.delta_seconds();
+            continue;
+        }
+
+        // Swimming replaces gravity with weaker water gravity, buoyancy and drag
+        if let Some(water) = water {
+            let mut gravity_multiplier =
+                controller.swim_gravity_multiplier * water.gravity_multiplier;
+            if !swim.at_surface() {
+                gravity_multiplier -= water.buoyancy;
+            }
+            velocity.0.y += gravity * gravity_multiplier * time.delta_seconds();
+            velocity.0.y /=
+                1.0 + controller.swim_drag * water.drag_multiplier * time.delta_seconds();
+            velocity.0.y = velocity
+                .0
+                .y
+                .max(-controller.swim_max_fall_speed * time.delta_seconds());
+            continue;
+        }
 
         // Apply gravity
-        let gravity_multiplier = if velocity.0.y > 0.0 {
+        let mut gravity_multiplier = if velocity.0.y > 0.0 {
             // controller.
             controller.gravity_up_multiplier
         } else if velocity.0.y < 0.0 {
@@ -90,6 +278,21 @@ fn player_input(
         } else {
             1.0
         };
+        if at_apex {
+            gravity_multiplier *= controller.apex_gravity_multiplier;
+        }
+        let mut max_fall_speed = controller.max_fall_speed;
+        if controller.fast_fall && !collisions.below && input_raw.y < 0.0 {
+            gravity_multiplier *= controller.fast_fall_gravity_multiplier;
+            // Fast falling never lowers the normal cap
+            max_fall_speed = max_fall_speed.max(controller.fast_fall_max_speed);
+        }
+        if glide.is_gliding() {
+            max_fall_speed = controller.glide_fall_speed;
+        }
         velocity.0.y += gravity * gravity_multiplier * time.delta_seconds();
+
+        // Limit fall speed
+        velocity.0.y = velocity.0.y.max(-max_fall_speed * time.delta_seconds());
     }
 }
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{CollisionInfo, JumpCount, PlayerVelocity},
+    dash::Dash,
+    events::Landed,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct JumpPadPlugin;
+
+impl Plugin for JumpPadPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<JumpPad>().add_system(
+            launch_from_jump_pads
+                .after(ControllerLabel::Events)
+                .after(ControllerLabel::RestoreVelocity)
+                // A jump pad's launch replaces any bounce off its surface material
+                .after(ControllerLabel::Bounce),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JumpPadLaunch {
+    /// Launch with a fixed velocity, in the same units as [`PlayerVelocity`].
+    Velocity(Vec2),
+    /// Launch straight up to reach a height, in the same units as
+    /// [`CharacterController::jump_height`].
+    Height(f32),
+}
+
+/// Launches characters which land on a collider, such as a spring or jump pad.
+///
+/// Launching resets the jump count and air dashes.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct JumpPad {
+    /// How characters are launched _(default: `JumpPadLaunch::Height(4.0)`)_
+    #[reflect(ignore)]
+    pub launch: JumpPadLaunch,
+    /// Launch multiplier while jump is held _(default: `1.0`)_
+    pub jump_held_multiplier: f32,
+}
+
+impl Default for JumpPad {
+    fn default() -> Self {
+        JumpPad::height(4.0)
+    }
+}
+
+impl JumpPad {
+    pub fn velocity(velocity: Vec2) -> Self {
+        JumpPad {
+            launch: JumpPadLaunch::Velocity(velocity),
+            jump_held_multiplier: 1.0,
+        }
+    }
+
+    pub fn height(height: f32) -> Self {
+        JumpPad {
+            launch: JumpPadLaunch::Height(height),
+            jump_held_multiplier: 1.0,
+        }
+    }
+
+    pub fn with_jump_held_multiplier(mut self, jump_held_multiplier: f32) -> Self {
+        self.jump_held_multiplier = jump_held_multiplier;
+        self
+    }
+}
+
+fn launch_from_jump_pads(
+    input: Res<Input<KeyCode>>,
+    mut landed_events: EventReader<Landed>,
+    mut controllers: Query<(
+        &CharacterController,
+        &mut PlayerVelocity,
+        &mut JumpCount,
+        &mut Dash,
+        &CollisionInfo,
+    )>,
+    jump_pads: Query<&JumpPad>,
+) {
+    let jump_held = input.pressed(KeyCode::Up) || input.pressed(KeyCode::Space);
+
+    for Landed { entity, .. } in landed_events.iter() {
+        if let Ok((controller, mut velocity, mut jump_count, mut dash, collisions)) =
+            controllers.get_mut(*entity)
+        {
+            let jump_pad = match collisions
+                .ground
+                .and_then(|ground| jump_pads.get(ground).ok())
+            {
+                Some(jump_pad) => jump_pad,
+                None => continue,
+            };
+
+            let multiplier = if jump_held {
+                jump_pad.jump_held_multiplier
+            } else {
+                1.0
+            };
+            match jump_pad.launch {
+                JumpPadLaunch::Velocity(launch_velocity) => {
+                    velocity.0 = launch_velocity * multiplier;
+                }
+                JumpPadLaunch::Height(height) => {
+                    velocity.0.y = controller.launch_velocity(height * multiplier);
+                }
+            }
+
+            jump_count.0 = 0;
+            dash.air_dash_count = 0;
+        }
+    }
+}
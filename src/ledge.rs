@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use impacted::CollisionShape;
+
+use crate::{
+    collisions::{CollisionInfo, PlayerVelocity, RaycastOrigins, SolidFilter},
+    dash::Dash,
+    events::WallSide,
+    gravity::Gravity,
+    ground_pound::GroundPounding,
+    input::ControllerInput,
+    ray_cast::{Oriented, Ray, RayCast},
+    CharacterController, ControllerLabel,
+};
+
+/// Time in seconds after letting go of a ledge before it can be grabbed again.
+const REGRAB_COOLDOWN: f32 = 0.25;
+
+pub(crate) struct LedgePlugin;
+
+impl Plugin for LedgePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LedgeGrab>()
+            .add_event::<LedgeGrabbed>()
+            .add_event::<LedgeClimbed>()
+            .add_event::<LedgeReleased>()
+            .add_system(
+                ledge_grab
+                    .label(ControllerLabel::Calculate)
+                    .label(ControllerLabel::LedgeGrab)
+                    .after(ControllerLabel::GroundPound)
+                    // Snapping onto a ledge moves the character
+                    .before(ControllerLabel::RayOrigins),
+            );
+    }
+}
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct LedgeGrab {
+    direction: f32,
+    ledge: Vec2,
+    climb_time_left: f32,
+    regrab_cooldown: f32,
+}
+
+impl LedgeGrab {
+    /// Whether the character is hanging from, or climbing onto, a ledge.
+    pub fn is_hanging(&self) -> bool {
+        self.direction != 0.0
+    }
+
+    pub fn is_climbing(&self) -> bool {
+        self.is_hanging() && self.climb_time_left > 0.0
+    }
+
+    /// Lets go of the ledge, waiting a moment before grabbing one again.
+    pub(crate) fn release(&mut self) {
+        self.direction = 0.0;
+        self.regrab_cooldown = REGRAB_COOLDOWN;
+    }
+
+    /// Position of the ledge corner being held.
+    pub fn ledge(&self) -> Option<Vec2> {
+        if self.is_hanging() {
+            Some(self.ledge)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sent when a character grabs onto a ledge.
+#[derive(Clone, Copy, Debug)]
+pub struct LedgeGrabbed {
+    pub entity: Entity,
+    pub side: WallSide,
+    pub ledge: Vec2,
+}
+
+/// Sent when a character finishes climbing up onto a ledge.
+#[derive(Clone, Copy, Debug)]
+pub struct LedgeClimbed {
+    pub entity: Entity,
+}
+
+/// Sent when a character lets go of a ledge and drops down.
+#[derive(Clone, Copy, Debug)]
+pub struct LedgeReleased {
+    pub entity: Entity,
+}
+
+fn ledge_grab(
+    time: Res<Time>,
+    input: Res<Input<KeyCode>>,
+    mut controllers: Query<
+        (
+            Entity,
+            &CharacterController,
+            &mut LedgeGrab,
+            &mut PlayerVelocity,
+            &mut Transform,
+            &CollisionShape,
+            &CollisionInfo,
+            &RaycastOrigins,
+            &ControllerInput,
+            &Gravity,
+            (&Dash, &GroundPounding),
+        ),
+        With<CharacterController>,
+    >,
+    colliders: Query<(Entity, &CollisionShape, &Transform), SolidFilter>,
+    mut ledge_grabbed_events: EventWriter<LedgeGrabbed>,
+    mut ledge_climbed_events: EventWriter<LedgeClimbed>,
+    mut ledge_released_events: EventWriter<LedgeReleased>,
+) {
+    for (
+        entity,
+        controller,
+        mut ledge,
+        mut velocity,
+        mut transform,
+        collider,
+        collisions,
+        raycast_origins,
+        controller_input,
+        gravity,
+        (dash, ground_pound),
+    ) in controllers.iter_mut()
+    {
+        let colliders = Oriented::new(&colliders, gravity);
+        ledge.regrab_cooldown = (ledge.regrab_cooldown - time.delta_seconds()).max(0.0);
+
+        if ledge.is_hanging() {
+            // Hold still while hanging, ignoring gravity
+            velocity.0 = Vec2::ZERO;
+
+            if ledge.is_climbing() {
+                ledge.climb_time_left -= time.delta_seconds();
+                if ledge.climb_time_left <= 0.0 {
+                    // Stand on top of the ledge
+                    let size = gravity.to_local(collider.bounds().size()).abs();
+                    let ledge_position = gravity.to_local(ledge.ledge);
+                    let position = Vec2::new(
+                        ledge_position.x + ledge.direction * size.x / 2.0,
+                        ledge_position.y + size.y / 2.0,
+                    );
+                    let z = transform.translation.z;
+                    transform.translation = gravity.to_world(position).extend(z);
+                    ledge.direction = 0.0;
+                    ledge_climbed_events.send(LedgeClimbed { entity });
+                }
+            } else if input.just_pressed(KeyCode::Up) || input.just_pressed(KeyCode::Space) {
+                ledge.climb_time_left = controller.ledge_climb_time.max(f32::EPSILON);
+            } else if input.just_pressed(KeyCode::Down) || controller_input.0.x == -ledge.direction
+            {
+                ledge.release();
+                ledge_released_events.send(LedgeReleased { entity });
+            }
+            continue;
+        }
+
+        if !controller.ledge_grab
+            || ledge.regrab_cooldown > 0.0
+            || dash.is_dashing()
+            || ground_pound.is_ground_pounding()
+            || collisions.below
+            || velocity.0.y >= 0.0
+        {
+            continue;
+        }
+
+        // Must be holding towards a wall being touched
+        let direction_x = controller_input.0.x;
+        if !((direction_x < 0.0 && collisions.left) || (direction_x > 0.0 && collisions.right)) {
+            continue;
+        }
+
+        let corner = if direction_x < 0.0 {
+            raycast_origins.top_left
+        } else {
+            raycast_origins.top_right
+        };
+        let top = corner.y + controller.skin_width;
+
+        // The wall must not continue past the top of the character
+        let wall_hit = colliders.ray_cast(
+            Ray::new(Vec2::new(corner.x, top), Vec2::X * direction_x)
+                .with_length(controller.skin_width + controller.ledge_grab_reach),
+        );
+        if wall_hit.is_some() {
+            continue;
+        }
+
+        // Find the top of the ledge if the character falls past it this frame
+        let probe_x =
+            corner.x + direction_x * (controller.skin_width + controller.ledge_grab_reach);
+        let ledge_hit = colliders.ray_cast(
+            Ray::new(Vec2::new(probe_x, top), -Vec2::Y)
+                .with_length(-velocity.0.y + controller.skin_width),
+        );
+        let ledge_y = match ledge_hit {
+            Some(hit) => hit.position.y,
+            None => continue,
+        };
+
+        // Make sure there is room to climb up onto the ledge
+        let size = gravity.to_local(collider.bounds().size()).abs();
+        let blocked = [probe_x, probe_x + direction_x * size.x]
+            .into_iter()
+            .any(|x| {
+                colliders
+                    .ray_cast(
+                        Ray::new(Vec2::new(x, ledge_y + controller.skin_width), Vec2::Y)
+                            .with_length(size.y),
+                    )
+                    .is_some()
+            });
+        if blocked {
+            continue;
+        }
+
+        ledge.direction = direction_x;
+        ledge.ledge = gravity.to_world(Vec2::new(
+            corner.x + direction_x * controller.skin_width,
+            ledge_y,
+        ));
+        ledge.climb_time_left = 0.0;
+        // Snap the top of the character to the ledge
+        velocity.0 = Vec2::new(0.0, ledge_y - top);
+
+        ledge_grabbed_events.send(LedgeGrabbed {
+            entity,
+            side: if direction_x < 0.0 {
+                WallSide::Left
+            } else {
+                WallSide::Right
+            },
+            ledge: ledge.ledge,
+        });
+    }
+}
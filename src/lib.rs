@@ -1,21 +1,50 @@
 #![allow(clippy::type_complexity)]
 
 use bevy::prelude::*;
+use climb::{Climb, ClimbPlugin};
 use collisions::{
     CollisionInfo, CollisionsPlugin, CoyoteStopwatch, JumpCount, PlayerVelocity, RaySpacing,
     RaycastOrigins,
 };
+use crouch::{Crouch, CrouchPlugin};
+use dash::{Dash, DashPlugin};
+use events::{Airtime, EventsPlugin, FallSpeed};
+use glide::{Glide, GlidePlugin};
+use gravity::{Gravity, GravityPlugin};
+use ground_pound::{GroundPoundPlugin, GroundPounding};
 pub use impacted;
+use impulse::{ImpulsePlugin, InputLockout};
 use input::VelocityXSmoothing;
+use jump_pad::JumpPadPlugin;
+use ledge::{LedgeGrab, LedgePlugin};
+use push::PushPlugin;
+use state::{ControllerState, Facing, StatePlugin};
+use surface::SurfacePlugin;
+use water::{Swim, WaterPlugin};
 
 pub use crate::controller::CharacterController;
 use crate::controller::ControllerPlugin;
+pub use crate::input::ControllerInput;
 use crate::input::InputPlugin;
 
+pub mod climb;
 pub mod collisions;
 pub mod controller;
+pub mod crouch;
+pub mod dash;
+pub mod events;
+pub mod glide;
+pub mod gravity;
+pub mod ground_pound;
+pub mod impulse;
 mod input;
+pub mod jump_pad;
+pub mod ledge;
+pub mod push;
 mod ray_cast;
+pub mod state;
+pub mod surface;
+pub mod water;
 
 pub struct CharacterControllerPlugin;
 
@@ -23,15 +52,40 @@ impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(ControllerPlugin)
             .add_plugin(InputPlugin)
-            .add_plugin(CollisionsPlugin);
+            .add_plugin(CollisionsPlugin)
+            .add_plugin(EventsPlugin)
+            .add_plugin(StatePlugin)
+            .add_plugin(DashPlugin)
+            .add_plugin(CrouchPlugin)
+            .add_plugin(LedgePlugin)
+            .add_plugin(SurfacePlugin)
+            .add_plugin(ImpulsePlugin)
+            .add_plugin(JumpPadPlugin)
+            .add_plugin(ClimbPlugin)
+            .add_plugin(WaterPlugin)
+            .add_plugin(GravityPlugin)
+            .add_plugin(GlidePlugin)
+            .add_plugin(GroundPoundPlugin)
+            .add_plugin(PushPlugin);
     }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, SystemLabel)]
 enum ControllerLabel {
+    Input,
+    Dash,
+    GroundPound,
+    LedgeGrab,
+    Impulse,
     Calculate,
+    RayOrigins,
     Collisions,
+    HorizontalCollisions,
+    Events,
+    State,
     Move,
+    RestoreVelocity,
+    Bounce,
 }
 
 #[derive(Bundle, Default)]
@@ -40,10 +94,24 @@ pub struct CharacterControllerBundle {
     pub jump_count: JumpCount,
     pub coyote_stopwatch: CoyoteStopwatch,
     pub velocity_x_smoothing: VelocityXSmoothing,
+    pub input: ControllerInput,
     pub velocity: PlayerVelocity,
     pub ray_spacing: RaySpacing,
     pub collisions: CollisionInfo,
     pub raycast_origins: RaycastOrigins,
+    pub airtime: Airtime,
+    pub fall_speed: FallSpeed,
+    pub state: ControllerState,
+    pub facing: Facing,
+    pub dash: Dash,
+    pub crouch: Crouch,
+    pub ledge_grab: LedgeGrab,
+    pub input_lockout: InputLockout,
+    pub climb: Climb,
+    pub swim: Swim,
+    pub gravity: Gravity,
+    pub glide: Glide,
+    pub ground_pound: GroundPounding,
 }
 
 impl CharacterControllerBundle {
@@ -89,3 +157,48 @@ fn smooth_damp(
 
     output
 }
+
+/// Moves `current` towards `target` along a `t^exponent` curve, taking `time` seconds to cover
+/// `range`.
+fn ease_towards(
+    current: f32,
+    target: f32,
+    range: f32,
+    time: f32,
+    exponent: f32,
+    delta_time: f32,
+) -> f32 {
+    let difference = target - current;
+    let range = range.max(difference.abs());
+    if range <= 0.0 || time <= 0.0 {
+        return target;
+    }
+
+    // Find how far along the curve the current value is, then step forward
+    let exponent = exponent.max(0.0001);
+    let remaining = difference.abs() / range;
+    let progress = (1.0 - remaining).powf(1.0 / exponent);
+    let progress = (progress + delta_time / time).min(1.0);
+    let remaining = 1.0 - progress.powf(exponent);
+
+    target - difference.signum() * remaining * range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ease_towards_reaches_target_after_time() {
+        assert_eq!(ease_towards(0.0, 10.0, 10.0, 1.0, 1.0, 1.0), 10.0);
+        assert_eq!(ease_towards(0.0, 10.0, 10.0, 0.0, 1.0, 0.1), 10.0);
+    }
+
+    #[test]
+    fn ease_towards_follows_curve() {
+        // Linear moves halfway in half the time, while squared starts slowly
+        assert!((ease_towards(0.0, 10.0, 10.0, 1.0, 1.0, 0.5) - 5.0).abs() < 1e-4);
+        assert!((ease_towards(0.0, 10.0, 10.0, 1.0, 2.0, 0.5) - 2.5).abs() < 1e-4);
+        assert!((ease_towards(10.0, 0.0, 10.0, 1.0, 2.0, 0.5) - 7.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    ray_cast::{Ray, RayCast},
+    CharacterController, ControllerLabel,
+};
+
+/// Distance inside the edges of a pushed collider to cast its collision rays from, so colliders
+/// flush against it are still hit.
+const PUSH_PROBE_INSET: f32 = 0.01;
+
+pub(crate) struct PushPlugin;
+
+impl Plugin for PushPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Pushable>()
+            .add_event::<Pushed>()
+            .add_system(
+                move_pushables
+                    .label(ControllerLabel::Move)
+                    .after(ControllerLabel::Collisions),
+            );
+    }
+}
+
+/// Lets characters push a collider along by walking into it, such as a crate.
+///
+/// Pushed colliders stop at anything solid in their way, including other pushables.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Pushable {
+    /// Multiplier for the pushing character's speed
+    pub speed_multiplier: f32,
+}
+
+impl Default for Pushable {
+    fn default() -> Self {
+        Pushable {
+            speed_multiplier: 0.5,
+        }
+    }
+}
+
+/// Sent when a character pushes a [`Pushable`] collider.
+#[derive(Clone, Copy, Debug)]
+pub struct Pushed {
+    pub entity: Entity,
+    pub pusher: Entity,
+    /// World space distance the collider was moved.
+    pub offset: Vec2,
+}
+
+/// Distance a pushed collider with `bounds`, as `(min, max)`, can move along `direction_x` before
+/// hitting something, up to `distance`.
+///
+/// `colliders` must not include the pushed collider itself.
+pub(crate) fn push_distance(
+    colliders: &impl RayCast,
+    (min, max): (Vec2, Vec2),
+    direction_x: f32,
+    distance: f32,
+) -> f32 {
+    if distance <= 0.0 {
+        return 0.0;
+    }
+
+    let face_x = if direction_x > 0.0 { max.x } else { min.x };
+    let origin_x = face_x - direction_x * PUSH_PROBE_INSET;
+    [
+        min.y + PUSH_PROBE_INSET,
+        (min.y + max.y) / 2.0,
+        max.y - PUSH_PROBE_INSET,
+    ]
+    .into_iter()
+    .filter_map(|y| {
+        colliders.ray_cast(
+            Ray::new(Vec2::new(origin_x, y), Vec2::X * direction_x)
+                .with_length(distance + PUSH_PROBE_INSET),
+        )
+    })
+    .fold(distance, |distance, hit| {
+        distance.min(hit.distance - PUSH_PROBE_INSET)
+    })
+}
+
+fn move_pushables(
+    mut pushed_events: EventReader<Pushed>,
+    mut pushables: Query<&mut Transform, (With<Pushable>, Without<CharacterController>)>,
+) {
+    // Several characters can push the same collider, which only moves by the largest push
+    let mut offsets: HashMap<Entity, Vec2> = HashMap::default();
+    for Pushed { entity, offset, .. } in pushed_events.iter() {
+        let largest = offsets.entry(*entity).or_default();
+        if offset.length_squared() > largest.length_squared() {
+            *largest = *offset;
+        }
+    }
+
+    for (entity, offset) in offsets {
+        if let Ok(mut transform) = pushables.get_mut(entity) {
+            transform.translation += offset.extend(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ray_cast::Edge;
+
+    #[test]
+    fn push_distance_stops_at_obstacles() {
+        let wall = Edge(Vec2::new(15.0, -5.0), Vec2::new(15.0, 20.0));
+        let bounds = (Vec2::ZERO, Vec2::splat(10.0));
+
+        assert!((push_distance(&wall, bounds, 1.0, 3.0) - 3.0).abs() < 1e-4);
+        assert!((push_distance(&wall, bounds, 1.0, 8.0) - 5.0).abs() < 1e-4);
+        assert!((push_distance(&wall, bounds, -1.0, 8.0) - 8.0).abs() < 1e-4);
+        assert_eq!(push_distance(&wall, bounds, 1.0, 0.0), 0.0);
+    }
+}
@@ -4,6 +4,8 @@ use bevy::{
 };
 use impacted::CollisionShape;
 
+use crate::gravity::Gravity;
+
 #[derive(Clone, Copy, Debug)]
 pub struct Ray {
     origin: Vec2,
@@ -30,6 +32,10 @@ impl Ray {
 pub struct RayHit {
     pub position: Vec2,
     pub distance: f32,
+    /// Unit normal of the edge that was hit, facing back towards the ray.
+    pub normal: Vec2,
+    /// Collider that was hit, when casting against a query.
+    pub entity: Option<Entity>,
 }
 
 pub trait RayCast {
@@ -61,14 +67,22 @@ impl RayCast for Edge {
         if t > 0.0 && t < 1.0 && u > 0.0 {
             let point = Vec2::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1));
             let distance = point.distance(ray.origin);
+            let mut normal = (self.1 - self.0).perp().normalize();
+            if normal.dot(ray.direction) > 0.0 {
+                normal = -normal;
+            }
             match ray.length {
                 Some(length) if length >= distance => Some(RayHit {
                     position: point,
                     distance,
+                    normal,
+                    entity: None,
                 }),
                 None => Some(RayHit {
                     position: point,
                     distance,
+                    normal,
+                    entity: None,
                 }),
                 _ => None,
             }
@@ -79,70 +93,146 @@ impl RayCast for Edge {
 }
 
 impl<'w, 's, 'q1, 'q2, F: WorldQuery> RayCast
-    for Query<'w, 's, (&'q1 CollisionShape, &'q2 Transform), F>
+    for Query<'w, 's, (Entity, &'q1 CollisionShape, &'q2 Transform), F>
+where
+    F: WorldQuery,
+    F::Fetch: FilterFetch,
+{
+    fn ray_cast(&self, ray: Ray) -> Option<RayHit> {
+        self.iter().fold(
+            None,
+            |acc: Option<RayHit>, (entity, collider, transform)| {
+                closer(acc, ray_cast_collider(entity, collider, transform, ray))
+            },
+        )
+    }
+}
+
+/// Ray casts against a query of colliders while ignoring one of them, such as the collider the
+/// ray is cast from.
+pub struct Excluding<'a, T> {
+    colliders: &'a T,
+    entity: Entity,
+}
+
+impl<'a, T> Excluding<'a, T> {
+    pub fn new(colliders: &'a T, entity: Entity) -> Self {
+        Excluding { colliders, entity }
+    }
+}
+
+impl<'a, 'w, 's, 'q1, 'q2, F: WorldQuery> RayCast
+    for Excluding<'a, Query<'w, 's, (Entity, &'q1 CollisionShape, &'q2 Transform), F>>
 where
     F: WorldQuery,
     F::Fetch: FilterFetch,
 {
     fn ray_cast(&self, ray: Ray) -> Option<RayHit> {
-        self.iter()
-            .fold(None, |acc: Option<RayHit>, (collider, transform)| {
-                let bounds = collider.bounds();
-                let top_left = Vec2::new(
-                    bounds.min().x + transform.translation.x,
-                    bounds.max().y + transform.translation.y,
-                );
-                let top_right = Vec2::new(
-                    bounds.max().x + transform.translation.x,
-                    bounds.max().y + transform.translation.y,
-                );
-                let bottom_left = Vec2::new(
-                    bounds.min().x + transform.translation.x,
-                    bounds.min().y + transform.translation.y,
-                );
-                let bottom_right = Vec2::new(
-                    bounds.max().x + transform.translation.x,
-                    bounds.min().y + transform.translation.y,
-                );
-
-                let edges = [
-                    Edge(top_left, top_right),
-                    Edge(top_right, bottom_right),
-                    Edge(bottom_right, bottom_left),
-                    Edge(bottom_left, top_left),
-                ];
-
-                let hit = edges.into_iter().fold(None, |acc: Option<RayHit>, edge| {
-                    let hit = edge.ray_cast(ray);
-
-                    match hit {
-                        Some(hit) => match acc {
-                            Some(previous_hit) => {
-                                if hit.distance < previous_hit.distance {
-                                    Some(hit)
-                                } else {
-                                    acc
-                                }
-                            }
-                            None => Some(hit),
-                        },
-                        None => acc,
-                    }
-                });
-
-                match hit {
-                    Some(hit) => match acc {
-                        Some(previous_hit) => {
-                            if hit.distance < previous_hit.distance {
-                                Some(hit)
-                            } else {
-                                acc
-                            }
-                        }
-                        None => Some(hit),
-                    },
-                    None => acc,
-                }
+        self.colliders
+            .iter()
+            .filter(|(entity, _, _)| *entity != self.entity)
+            .fold(
+                None,
+                |acc: Option<RayHit>, (entity, collider, transform)| {
+                    closer(acc, ray_cast_collider(entity, collider, transform, ray))
+                },
+            )
+    }
+}
+
+/// Ray casts against world space bounding boxes, as `(entity, min, max)`.
+pub struct Boxes(pub Vec<(Entity, Vec2, Vec2)>);
+
+impl RayCast for Boxes {
+    fn ray_cast(&self, ray: Ray) -> Option<RayHit> {
+        self.0
+            .iter()
+            .fold(None, |acc: Option<RayHit>, (entity, min, max)| {
+                closer(acc, ray_cast_bounds(*entity, *min, *max, ray))
             })
     }
 }
+
+impl<A: RayCast, B: RayCast> RayCast for (A, B) {
+    fn ray_cast(&self, ray: Ray) -> Option<RayHit> {
+        closer(self.0.ray_cast(ray), self.1.ray_cast(ray))
+    }
+}
+
+impl<T: RayCast> RayCast for &T {
+    fn ray_cast(&self, ray: Ray) -> Option<RayHit> {
+        (*self).ray_cast(ray)
+    }
+}
+
+fn ray_cast_collider(
+    entity: Entity,
+    collider: &CollisionShape,
+    transform: &Transform,
+    ray: Ray,
+) -> Option<RayHit> {
+    let bounds = collider.bounds();
+    let offset = transform.translation.truncate();
+    let min = Vec2::new(bounds.min().x, bounds.min().y) + offset;
+    let max = Vec2::new(bounds.max().x, bounds.max().y) + offset;
+    ray_cast_bounds(entity, min, max, ray)
+}
+
+fn ray_cast_bounds(entity: Entity, min: Vec2, max: Vec2, ray: Ray) -> Option<RayHit> {
+    let top_left = Vec2::new(min.x, max.y);
+    let top_right = max;
+    let bottom_left = min;
+    let bottom_right = Vec2::new(max.x, min.y);
+
+    let edges = [
+        Edge(top_left, top_right),
+        Edge(top_right, bottom_right),
+        Edge(bottom_right, bottom_left),
+        Edge(bottom_left, top_left),
+    ];
+
+    edges
+        .into_iter()
+        .fold(None, |acc: Option<RayHit>, edge| {
+            closer(acc, edge.ray_cast(ray))
+        })
+        .map(|hit| RayHit {
+            entity: Some(entity),
+            ..hit
+        })
+}
+
+fn closer(a: Option<RayHit>, b: Option<RayHit>) -> Option<RayHit> {
+    match (a, b) {
+        (Some(a), Some(b)) if b.distance < a.distance => Some(b),
+        (Some(a), _) => Some(a),
+        (None, b) => b,
+    }
+}
+
+/// Ray casts in a character's gravity frame, converting rays to world space and hits back.
+pub struct Oriented<'a, T> {
+    colliders: &'a T,
+    gravity: &'a Gravity,
+}
+
+impl<'a, T: RayCast> Oriented<'a, T> {
+    pub fn new(colliders: &'a T, gravity: &'a Gravity) -> Self {
+        Oriented { colliders, gravity }
+    }
+}
+
+impl<'a, T: RayCast> RayCast for Oriented<'a, T> {
+    fn ray_cast(&self, ray: Ray) -> Option<RayHit> {
+        let world_ray = Ray {
+            origin: self.gravity.to_world(ray.origin),
+            direction: self.gravity.to_world(ray.direction),
+            length: ray.length,
+        };
+        self.colliders.ray_cast(world_ray).map(|hit| RayHit {
+            position: self.gravity.to_local(hit.position),
+            normal: self.gravity.to_local(hit.normal),
+            ..hit
+        })
+    }
+}
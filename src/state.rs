@@ -0,0 +1,222 @@
+use bevy::prelude::*;
+
+use crate::{
+    climb::Climb,
+    collisions::{CollisionInfo, PlayerVelocity},
+    crouch::Crouch,
+    dash::Dash,
+    glide::Glide,
+    ground_pound::GroundPounding,
+    input::ControllerInput,
+    ledge::LedgeGrab,
+    water::Swim,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct StatePlugin;
+
+impl Plugin for StatePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ControllerState>()
+            .register_type::<Facing>()
+            .add_event::<StateChanged>()
+            .add_system(
+                update_controller_state
+                    .label(ControllerLabel::State)
+                    .after(ControllerLabel::Collisions),
+            )
+            .add_system(
+                update_facing
+                    .label(ControllerLabel::State)
+                    .after(ControllerLabel::Collisions),
+            )
+            .add_system(flip_sprites.after(ControllerLabel::State));
+    }
+}
+
+/// High level state of a character, updated every frame.
+///
+/// Grounded states are only used while touching the ground,
+/// so a character is `Falling` during coyote time.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect_value(Component, PartialEq)]
+pub enum ControllerState {
+    #[default]
+    Idle,
+    Running,
+    Jumping,
+    Falling,
+    WallSliding,
+    Dashing,
+    Crouching,
+    LedgeHanging,
+    LedgeClimbing,
+    Climbing,
+    Swimming,
+    Gliding,
+    GroundPounding,
+    Pushing,
+}
+
+/// Horizontal direction a character is facing.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect_value(Component, PartialEq)]
+pub enum Facing {
+    Left,
+    #[default]
+    Right,
+}
+
+impl Facing {
+    /// Unit direction along the x axis.
+    pub fn direction(&self) -> f32 {
+        match self {
+            Facing::Left => -1.0,
+            Facing::Right => 1.0,
+        }
+    }
+}
+
+/// Sent when a character's [`ControllerState`] changes.
+#[derive(Clone, Copy, Debug)]
+pub struct StateChanged {
+    pub entity: Entity,
+    pub from: ControllerState,
+    pub to: ControllerState,
+}
+
+fn update_controller_state(
+    mut query: Query<(
+        Entity,
+        &mut ControllerState,
+        &PlayerVelocity,
+        &CollisionInfo,
+        &ControllerInput,
+        &Dash,
+        &Crouch,
+        &LedgeGrab,
+        &Climb,
+        &Swim,
+        &Glide,
+        &GroundPounding,
+    )>,
+    mut state_changed_events: EventWriter<StateChanged>,
+) {
+    for (
+        entity,
+        mut state,
+        velocity,
+        collisions,
+        input,
+        dash,
+        crouch,
+        ledge,
+        climb,
+        swim,
+        glide,
+        ground_pound,
+    ) in query.iter_mut()
+    {
+        let pushing_wall =
+            (input.0.x < 0.0 && collisions.left) || (input.0.x > 0.0 && collisions.right);
+
+        let new_state = if ledge.is_climbing() {
+            ControllerState::LedgeClimbing
+        } else if ledge.is_hanging() {
+            ControllerState::LedgeHanging
+        } else if dash.is_dashing() {
+            ControllerState::Dashing
+        } else if ground_pound.is_ground_pounding() {
+            ControllerState::GroundPounding
+        } else if climb.is_climbing() {
+            ControllerState::Climbing
+        } else if swim.is_swimming() {
+            ControllerState::Swimming
+        } else if glide.is_gliding() {
+            ControllerState::Gliding
+        } else if collisions.below && crouch.is_crouching() {
+            ControllerState::Crouching
+        } else if collisions.below && collisions.pushing.is_some() {
+            ControllerState::Pushing
+        } else if collisions.below {
+            if input.0.x != 0.0 && !pushing_wall {
+                ControllerState::Running
+            } else {
+                ControllerState::Idle
+            }
+        } else if velocity.0.y > 0.0 {
+            ControllerState::Jumping
+        } else if pushing_wall {
+            ControllerState::WallSliding
+        } else {
+            ControllerState::Falling
+        };
+
+        if *state != new_state {
+            state_changed_events.send(StateChanged {
+                entity,
+                from: *state,
+                to: new_state,
+            });
+            *state = new_state;
+        }
+    }
+}
+
+fn update_facing(
+    mut query: Query<(
+        &mut Facing,
+        &PlayerVelocity,
+        &CollisionInfo,
+        &ControllerInput,
+    )>,
+) {
+    for (mut facing, velocity, collisions, input) in query.iter_mut() {
+        // Prefer input, falling back to velocity when not pressed against a wall
+        let direction_x = if input.0.x != 0.0 {
+            input.0.x
+        } else if !collisions.left && !collisions.right {
+            velocity.0.x
+        } else {
+            0.0
+        };
+
+        let new_facing = if direction_x < 0.0 {
+            Facing::Left
+        } else if direction_x > 0.0 {
+            Facing::Right
+        } else {
+            continue;
+        };
+
+        if *facing != new_facing {
+            *facing = new_facing;
+        }
+    }
+}
+
+fn flip_sprites(
+    mut query: Query<
+        (
+            &CharacterController,
+            &Facing,
+            Option<&mut Sprite>,
+            Option<&mut TextureAtlasSprite>,
+        ),
+        Or<(Changed<Facing>, Changed<CharacterController>)>,
+    >,
+) {
+    for (controller, facing, sprite, atlas_sprite) in query.iter_mut() {
+        if !controller.flip_sprite {
+            continue;
+        }
+
+        let flip_x = *facing == Facing::Left;
+        if let Some(mut sprite) = sprite {
+            sprite.flip_x = flip_x;
+        }
+        if let Some(mut atlas_sprite) = atlas_sprite {
+            atlas_sprite.flip_x = flip_x;
+        }
+    }
+}
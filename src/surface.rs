@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    collisions::{CollisionInfo, PlayerVelocity},
+    events::Landed,
+    ControllerLabel,
+};
+
+/// Landing speeds which would bounce back slower than this come to rest instead.
+const MIN_BOUNCE_SPEED: f32 = 1.0;
+
+pub(crate) struct SurfacePlugin;
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SurfaceMaterial>()
+            .register_type::<SurfaceVelocity>()
+            .add_system(
+                bounce
+                    .label(ControllerLabel::Bounce)
+                    .after(ControllerLabel::Events)
+                    .after(ControllerLabel::RestoreVelocity),
+            );
+    }
+}
+
+/// Changes how characters move while standing on a collider.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct SurfaceMaterial {
+    /// Multiplier for how quickly characters slow down and turn around, lower is more slippery
+    pub friction: f32,
+    /// Multiplier for how quickly characters speed up
+    pub acceleration_multiplier: f32,
+    /// Multiplier for movement speed
+    pub max_speed_multiplier: f32,
+    /// Fraction of the landing speed to bounce back up with
+    pub bounciness: f32,
+}
+
+impl SurfaceMaterial {
+    pub const ICE: SurfaceMaterial = SurfaceMaterial {
+        friction: 0.1,
+        acceleration_multiplier: 0.25,
+        max_speed_multiplier: 1.0,
+        bounciness: 0.0,
+    };
+
+    pub const MUD: SurfaceMaterial = SurfaceMaterial {
+        friction: 2.0,
+        acceleration_multiplier: 0.5,
+        max_speed_multiplier: 0.5,
+        bounciness: 0.0,
+    };
+
+    pub const TRAMPOLINE: SurfaceMaterial = SurfaceMaterial {
+        friction: 1.0,
+        acceleration_multiplier: 1.0,
+        max_speed_multiplier: 1.0,
+        bounciness: 0.9,
+    };
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        SurfaceMaterial {
+            friction: 1.0,
+            acceleration_multiplier: 1.0,
+            max_speed_multiplier: 1.0,
+            bounciness: 0.0,
+        }
+    }
+}
+
+/// Carries characters standing on, or pressed against, a collider along its surface, such as a
+/// conveyor belt. Measured in units per second.
+///
+/// Only the component along the surface applies, so `x` moves characters standing on top and `y`
+/// moves characters pressed against the sides.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct SurfaceVelocity(pub Vec2);
+
+fn bounce(
+    mut landed_events: EventReader<Landed>,
+    mut controllers: Query<(&mut PlayerVelocity, &CollisionInfo)>,
+    surfaces: Query<&SurfaceMaterial>,
+) {
+    for Landed {
+        entity, fall_speed, ..
+    } in landed_events.iter()
+    {
+        if let Ok((mut velocity, collisions)) = controllers.get_mut(*entity) {
+            let bounciness = collisions
+                .ground
+                .and_then(|ground| surfaces.get(ground).ok())
+                .map(|surface| surface.bounciness)
+                .unwrap_or_default();
+
+            let bounce_speed = fall_speed * bounciness;
+            if bounce_speed >= MIN_BOUNCE_SPEED {
+                velocity.0.y = bounce_speed;
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use impacted::CollisionShape;
+
+use crate::{
+    collisions::{bounds_overlap, world_bounds, JumpCount, PlayerVelocity},
+    gravity::Gravity,
+    CharacterController, ControllerLabel,
+};
+
+pub(crate) struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Water>()
+            .register_type::<Swim>()
+            .add_event::<EnteredWater>()
+            .add_event::<ExitedWater>()
+            .add_system(
+                detect_water
+                    .label(ControllerLabel::Calculate)
+                    .before(ControllerLabel::Input),
+            );
+    }
+}
+
+/// Marks a collider as a body of water which characters swim in.
+///
+/// Water colliders don't block movement. The settings here are multiplied with the swim settings
+/// on [`CharacterController`].
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Water {
+    /// Multiplier for gravity while swimming in this water
+    pub gravity_multiplier: f32,
+    /// Multiplier for drag while swimming in this water
+    pub drag_multiplier: f32,
+    /// Fraction of gravity pushing submerged characters back up to the surface
+    pub buoyancy: f32,
+}
+
+impl Default for Water {
+    fn default() -> Self {
+        Water {
+            gravity_multiplier: 1.0,
+            drag_multiplier: 1.0,
+            buoyancy: 0.0,
+        }
+    }
+}
+
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Swim {
+    #[reflect(ignore)]
+    water: Option<Entity>,
+    at_surface: bool,
+}
+
+impl Swim {
+    pub fn is_swimming(&self) -> bool {
+        self.water.is_some()
+    }
+
+    /// Water collider being swum in.
+    pub fn water(&self) -> Option<Entity> {
+        self.water
+    }
+
+    /// Whether the character's head is above the water, so it can jump out.
+    pub fn at_surface(&self) -> bool {
+        self.is_swimming() && self.at_surface
+    }
+}
+
+/// Sent when a character enters water.
+#[derive(Clone, Copy, Debug)]
+pub struct EnteredWater {
+    pub entity: Entity,
+    pub water: Entity,
+    /// Velocity when hitting the water, for sizing splashes.
+    pub velocity: Vec2,
+}
+
+/// Sent when a character leaves water.
+#[derive(Clone, Copy, Debug)]
+pub struct ExitedWater {
+    pub entity: Entity,
+    pub water: Entity,
+    pub velocity: Vec2,
+}
+
+fn detect_water(
+    mut controllers: Query<
+        (
+            Entity,
+            &mut Swim,
+            &mut JumpCount,
+            &PlayerVelocity,
+            &CollisionShape,
+            &Transform,
+            &Gravity,
+        ),
+        With<CharacterController>,
+    >,
+    waters: Query<
+        (Entity, &CollisionShape, &Transform),
+        (With<Water>, Without<CharacterController>),
+    >,
+    mut entered_water_events: EventWriter<EnteredWater>,
+    mut exited_water_events: EventWriter<ExitedWater>,
+) {
+    for (entity, mut swim, mut jump_count, velocity, collider, transform, gravity) in
+        controllers.iter_mut()
+    {
+        // Compare bounds relative to gravity, so the surface is always the top of the water
+        let (min, max) = gravity.local_bounds(world_bounds(collider, transform));
+        // Only swim once the character's center is under water, so wading stays on foot
+        let water = waters
+            .iter()
+            .map(|(water, water_collider, water_transform)| {
+                let water_bounds = world_bounds(water_collider, water_transform);
+                (water, gravity.local_bounds(water_bounds))
+            })
+            .find(|(_, water_bounds)| {
+                bounds_overlap((min, max), *water_bounds)
+                    && (min.y + max.y) / 2.0 < water_bounds.1.y
+            });
+
+        swim.at_surface = water.map_or(false, |(_, water_bounds)| max.y > water_bounds.1.y);
+
+        let water = water.map(|(water, _)| water);
+        if swim.water == water {
+            continue;
+        }
+
+        if let Some(previous) = swim.water {
+            exited_water_events.send(ExitedWater {
+                entity,
+                water: previous,
+                velocity: velocity.0,
+            });
+        }
+        if let Some(water) = water {
+            // Entering water refreshes air jumps
+            jump_count.0 = 0;
+            entered_water_events.send(EnteredWater {
+                entity,
+                water,
+                velocity: velocity.0,
+            });
+        }
+        swim.water = water;
+    }
+}